@@ -0,0 +1,208 @@
+//! Runs the simulation without the desktop app and writes a summary of how the economy developed.
+//!
+//! Every random value comes from the seed, so the same seed and config always give the same summary.
+
+use incumbency_desktop::{
+    common::{
+        config::load_config_from,
+        errors::{Error, IncResult, Severity},
+    },
+    game::{
+        generation::{generate_game, stabilize_game},
+        manager::pass_day,
+        sink::{GameEvent, GameEventSink, NoopEventSink},
+        state_manager::GameStateSafe,
+        structs::GameState,
+    },
+};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+const DEFAULT_DAYS: u32 = 360;
+const DEFAULT_CONFIG_PATH: &str = "./game_config.toml";
+const USAGE: &str =
+    "Usage: incumbency-sim [--days <days>] [--seed <seed>] [--config <path>] [--output <path>]";
+
+struct Options {
+    days: u32,
+    seed: u64,
+    config_path: String,
+    output_path: Option<String>,
+}
+
+#[derive(Default, Serialize)]
+struct Series {
+    population: Vec<i64>,
+    government_balance: Vec<i64>,
+    expected_balance: Vec<i64>,
+    unemployed_count: Vec<i64>,
+    average_welfare: Vec<i64>,
+    average_welfare_unemployed: Vec<i64>,
+    business_count: Vec<i64>,
+    homeless_count: Vec<i64>,
+    vacancies: Vec<i64>,
+    household_debt: Vec<i64>,
+    public_debt: Vec<i64>,
+    consumer_price_index: Vec<f32>,
+    infected_count: Vec<i64>,
+}
+
+impl Series {
+    fn record(&mut self, state: &GameState) {
+        self.population.push(state.people.len() as i64);
+        self.government_balance.push(state.government_balance);
+        self.expected_balance.push(state.expected_balance);
+        self.unemployed_count.push(state.unemployed_count as i64);
+        self.average_welfare.push(state.average_welfare as i64);
+        self.average_welfare_unemployed
+            .push(state.average_welfare_unemployed as i64);
+        self.business_count.push(state.businesses.len() as i64);
+        self.homeless_count
+            .push(state.housing.homeless_count as i64);
+        self.vacancies.push(state.labour_market.vacancies as i64);
+        self.household_debt.push(state.household_debt.total_owed);
+        self.public_debt.push(state.public_debt.owed);
+        self.consumer_price_index
+            .push(state.prices.consumer_price_index);
+        self.infected_count
+            .push(state.epidemic.infected_count as i64);
+    }
+}
+
+#[derive(Serialize)]
+struct Summary {
+    seed: u64,
+    days_run: u32,
+    final_date: String,
+    errors: Vec<String>,
+    series: Series, // One value at the end of each day
+}
+
+/// Keeps the errors the game reports, dated by the day they happened on
+#[derive(Default)]
+struct ErrorSink {
+    date: Mutex<String>,
+    errors: Mutex<Vec<String>>,
+    fatal: Mutex<Option<Error>>,
+}
+
+impl GameEventSink for ErrorSink {
+    fn emit(&self, event: GameEvent) -> IncResult<()> {
+        match event {
+            GameEvent::NewDay(payload) => *self.date.lock().unwrap() = payload.date,
+            GameEvent::Error(err) if err.severity() == Severity::Fatal as u8 => {
+                *self.fatal.lock().unwrap() = Some(err);
+            }
+            GameEvent::Error(err) => {
+                let date = self.date.lock().unwrap();
+                self.errors
+                    .lock()
+                    .unwrap()
+                    .push(format!("{}: {}", date, err));
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        days: DEFAULT_DAYS,
+        seed: rand::random(),
+        config_path: DEFAULT_CONFIG_PATH.to_string(),
+        output_path: None,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("{} requires a value", arg))
+        };
+
+        match arg.as_str() {
+            "--days" => {
+                options.days = value()?
+                    .parse()
+                    .map_err(|_| "--days must be a positive number".to_string())?
+            }
+            "--seed" => {
+                options.seed = value()?
+                    .parse()
+                    .map_err(|_| "--seed must be a positive number".to_string())?
+            }
+            "--config" => options.config_path = value()?,
+            "--output" => options.output_path = Some(value()?),
+            _ => return Err(format!("Unknown argument '{}'", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn run(options: &Options) -> IncResult<Summary> {
+    let config = load_config_from(&options.config_path)?;
+    let state_mux: GameStateSafe = Arc::new(Mutex::new(GameState::default()));
+
+    generate_game(&state_mux, &config, &NoopEventSink, options.seed)?;
+    stabilize_game(&state_mux, &config, &NoopEventSink)?;
+
+    let sink = ErrorSink::default();
+    let cancel = CancellationToken::new();
+    let mut series = Series::default();
+    let mut days_run = 0;
+
+    for _ in 0..options.days {
+        if !pass_day(&state_mux, &sink, &config, &cancel) {
+            return Err(sink
+                .fatal
+                .into_inner()
+                .unwrap()
+                .unwrap_or(Error::FatalUnexpected));
+        }
+
+        series.record(&state_mux.lock().unwrap());
+        days_run += 1;
+    }
+
+    let final_date = state_mux.lock().unwrap().date.get_date_string();
+    Ok(Summary {
+        seed: options.seed,
+        days_run,
+        final_date,
+        errors: sink.errors.into_inner().unwrap(),
+        series,
+    })
+}
+
+fn write_summary(summary: &Summary, output_path: Option<&str>) -> IncResult<()> {
+    let contents = serde_json::to_string_pretty(summary)?;
+
+    match output_path {
+        Some(path) => std::fs::write(path, contents)?,
+        None => println!("{}", contents),
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let result =
+        run(&options).and_then(|summary| write_summary(&summary, options.output_path.as_deref()));
+
+    if let Err(err) = result {
+        eprintln!("Simulation failed with seed {}: {}", options.seed, err);
+        std::process::exit(1);
+    }
+}
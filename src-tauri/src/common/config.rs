@@ -15,6 +15,14 @@ pub struct Config {
     pub associate_degree: EducationConfig,
     pub bachelors: EducationConfig,
     pub advanced_degree: EducationConfig,
+
+    pub leisure: ProductConfig,
+    pub food: ProductConfig,
+    pub housing: ProductConfig,
+    pub furniture: ProductConfig,
+    pub healthcare_goods: ProductConfig,
+
+    pub pension: PensionConfig,
 }
 
 #[derive(Deserialize)]
@@ -23,6 +31,22 @@ pub struct EducationConfig {
     pub salary_range: ConfigRange,
 }
 
+#[derive(Deserialize)]
+pub struct ProductConfig {
+    #[serde(default)] // Only needed by products bought on purchase days
+    pub demand_weight: f32,
+    #[serde(default)]
+    pub income_weight: f32,
+    pub price_range: ConfigRange,
+}
+
+#[derive(Deserialize)]
+pub struct PensionConfig {
+    pub retirement_age: i32,
+    pub replacement_rate: f32, // Part of the individual's average career salary paid as their pension
+    pub minimum_pension: i32,
+}
+
 #[derive(Deserialize)]
 pub struct ConfigRange {
     pub min: i32,
@@ -30,6 +54,10 @@ pub struct ConfigRange {
 }
 
 pub fn load_config() -> IncResult<Config> {
-    let config_contents = fs::read_to_string(CONFIG_PATH)?;
+    load_config_from(CONFIG_PATH)
+}
+
+pub fn load_config_from(path: &str) -> IncResult<Config> {
+    let config_contents = fs::read_to_string(path)?;
     Ok(toml::from_str(config_contents.as_str())?)
 }
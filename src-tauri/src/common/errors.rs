@@ -6,6 +6,9 @@ pub enum Error {
     Toml(#[from] toml::de::Error),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
+    #[cfg(feature = "desktop")]
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
 
     #[error("{0}")]
     Warning(String),
@@ -1,7 +1,17 @@
-use super::errors::IncResult;
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use super::{
+    errors::{Error, IncResult},
+    save_format::{deserialize_save, serialize_save},
+};
+#[cfg(feature = "desktop")]
+use crate::game::state_manager::GameStateSafe;
+use crate::game::structs::GameState;
 
 const DATA_PATH: &str = "./data";
 const SAVES_PATH: &str = "./data/saves";
+const SAVE_FILE_NAME: &str = "state.json";
 
 fn check_data_directories() -> IncResult<()> {
     if !std::path::Path::new(DATA_PATH).exists() {
@@ -18,13 +28,131 @@ fn check_data_directories() -> IncResult<()> {
     Ok(())
 }
 
-// pub fn create_save(name: String) -> IncResult<()> {
-//   std::fs::create_dir(format!("{}/{}", SAVES_PATH, name))?;
-//   Ok(())
-// }
+/// Save names are used as directory names, so they must name a single directory inside the saves directory
+pub fn validate_save_name(name: &str) -> IncResult<()> {
+    if name.is_empty() {
+        return Err(Error::Warning("A save must have a name.".to_string()));
+    }
+
+    let is_file_name = std::path::Path::new(name).file_name() == Some(std::ffi::OsStr::new(name));
+    if name == "." || !is_file_name || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(Error::Warning(format!(
+            "'{}' is not a valid save name.",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+fn get_save_path(name: &str) -> String {
+    format!("{}/{}", SAVES_PATH, name)
+}
+
+fn get_save_file_path(name: &str) -> String {
+    format!("{}/{}", get_save_path(name), SAVE_FILE_NAME)
+}
 
+pub fn create_save(name: &str) -> IncResult<()> {
+    check_data_directories()?;
+    validate_save_name(name)?;
+
+    std::fs::create_dir_all(get_save_path(name))?;
+    Ok(())
+}
+
+/// Serializes the entire game state into the save's directory, overwriting the previous save
+pub fn write_save(name: &str, state: &GameState) -> IncResult<()> {
+    create_save(name)?;
+
+    std::fs::write(get_save_file_path(name), serialize_save(state)?)?;
+
+    Ok(())
+}
+
+pub fn read_save(name: &str) -> IncResult<GameState> {
+    check_data_directories()?;
+    validate_save_name(name)?;
+
+    let path = get_save_file_path(name);
+    if !std::path::Path::new(&path).exists() {
+        return Err(Error::Danger(format!(
+            "Save '{}' could not be found.",
+            name
+        )));
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    let mut state = deserialize_save(&contents)?;
+    state.name = name.to_string();
+
+    Ok(state)
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn check_save_exists(name: String) -> IncResult<bool> {
     check_data_directories()?;
-    Ok(std::path::Path::new(&format!("{}/{}", SAVES_PATH, name)).exists())
+    validate_save_name(&name)?;
+    Ok(std::path::Path::new(&get_save_path(&name)).exists())
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn save_game(state_mux: State<'_, GameStateSafe>) -> IncResult<()> {
+    let state = state_mux.lock().unwrap();
+    write_save(&state.name, &state)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn list_saves() -> IncResult<Vec<String>> {
+    check_data_directories()?;
+
+    let mut saves: Vec<String> = Vec::new();
+
+    for entry in std::fs::read_dir(SAVES_PATH)? {
+        let entry = entry?;
+        if !entry.path().join(SAVE_FILE_NAME).exists() {
+            continue;
+        }
+
+        if let Some(name) = entry.file_name().to_str() {
+            saves.push(name.to_string());
+        }
+    }
+
+    saves.sort();
+    Ok(saves)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn delete_save(name: String) -> IncResult<()> {
+    check_data_directories()?;
+    validate_save_name(&name)?;
+
+    let path = get_save_path(&name);
+    if !std::path::Path::new(&path).exists() {
+        return Err(Error::Warning(format!("Save '{}' does not exist.", name)));
+    }
+
+    std::fs::remove_dir_all(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_names_stay_inside_the_saves_directory() {
+        for name in ["", ".", "..", "/", "\\", "../saves", "a/b", "a\\b", "save/"] {
+            assert!(validate_save_name(name).is_err(), "{:?}", name);
+        }
+
+        for name in ["My Game", "game.2", ".hidden"] {
+            assert!(validate_save_name(name).is_ok(), "{:?}", name);
+        }
+    }
 }
@@ -2,4 +2,5 @@ pub mod config;
 pub mod errors;
 pub mod filesystem;
 pub mod payloads;
+pub mod save_format;
 pub mod util;
@@ -0,0 +1,759 @@
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+use super::{
+    errors::{Error, IncResult},
+    util::{GameRng, SlotArray},
+};
+use crate::{
+    entities::person::{
+        debt::{DebtType, STARTING_CREDIT_SCORE},
+        education::{EXPECTED_SPENDING_PER_PUPIL, SCHOOL_STARTING_AGE},
+    },
+    game::{
+        generation::STARTING_MARKET_RENT,
+        state_manager::{EMPTY_DATA, THREE_YEAR_DAYS},
+        structs::{
+            EducationState, EpidemicState, GameState, HospitalChargesRule, HouseholdDebtState,
+            HousingState, HousingSubsidyRule, IncomeTaxState, InheritanceTaxRule,
+            LabourMarketState, MinimumWageRule, PensionRule, PensionState, PriceState,
+            PublicDebtState, QuarantineRule, RentCapRule, SalesTaxRule, TuitionRule,
+        },
+    },
+};
+
+/// Bump this whenever the layout of a saved struct changes, and add a migration from the previous version
+pub const SAVE_FORMAT_VERSION: u32 = 21;
+
+type Migration = fn(&mut Value) -> IncResult<()>;
+
+/// Migration at index `i` upgrades the game state from version `i + 1` to version `i + 2`
+const MIGRATIONS: [Migration; (SAVE_FORMAT_VERSION - 1) as usize] = [
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+    migrate_v8_to_v9,
+    migrate_v9_to_v10,
+    migrate_v10_to_v11,
+    migrate_v11_to_v12,
+    migrate_v12_to_v13,
+    migrate_v13_to_v14,
+    migrate_v14_to_v15,
+    migrate_v15_to_v16,
+    migrate_v16_to_v17,
+    migrate_v17_to_v18,
+    migrate_v18_to_v19,
+    migrate_v19_to_v20,
+    migrate_v20_to_v21,
+];
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveHeader {
+    pub version: u32,
+}
+
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    header: SaveHeader,
+    state: &'a GameState,
+}
+
+pub fn serialize_save(state: &GameState) -> IncResult<String> {
+    let save = SaveFile {
+        header: SaveHeader {
+            version: SAVE_FORMAT_VERSION,
+        },
+        state,
+    };
+
+    Ok(serde_json::to_string(&save)?)
+}
+
+/// Reads a save of any supported version, upgrading it to the current game state layout
+pub fn deserialize_save(contents: &str) -> IncResult<GameState> {
+    let save: Value = serde_json::from_str(contents)
+        .map_err(|err| Error::Danger(format!("The save file is corrupted: {}", err)))?;
+
+    let (version, mut state) = split_save(save)?;
+    migrate(version, &mut state)?;
+
+    serde_json::from_value(state).map_err(|err| {
+        Error::Danger(format!(
+            "The save could not be loaded after upgrading it from version {}: {}",
+            version, err
+        ))
+    })
+}
+
+fn split_save(mut save: Value) -> IncResult<(u32, Value)> {
+    // Version 1 saves did not have a header, the file was only the game state
+    if save.get("header").is_none() {
+        return Ok((1, save));
+    }
+
+    let version = save["header"]
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| {
+            Error::Danger("The save header does not have a valid version.".to_string())
+        })?;
+
+    let state = save
+        .get_mut("state")
+        .map(Value::take)
+        .ok_or_else(|| Error::Danger("The save does not contain a game state.".to_string()))?;
+
+    Ok((version as u32, state))
+}
+
+fn migrate(version: u32, state: &mut Value) -> IncResult<()> {
+    if version == 0 || version > SAVE_FORMAT_VERSION {
+        return Err(Error::Danger(format!(
+            "Save format version {} is not supported, the latest supported version is {}.",
+            version, SAVE_FORMAT_VERSION
+        )));
+    }
+
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        migration(state)?;
+    }
+
+    Ok(())
+}
+
+fn as_object_mut(value: &mut Value) -> IncResult<&mut Map<String, Value>> {
+    value
+        .as_object_mut()
+        .ok_or_else(|| Error::Danger("The saved game state is not an object.".to_string()))
+}
+
+/// Version 2 only introduced the save header, the game state is unchanged
+fn migrate_v1_to_v2(_state: &mut Value) -> IncResult<()> {
+    Ok(())
+}
+
+/// Version 3 added the game's seeded rng, older saves were never seeded so they are given a new seed
+fn migrate_v2_to_v3(state: &mut Value) -> IncResult<()> {
+    let seed: u64 = rand::random();
+    let state = as_object_mut(state)?;
+
+    state.insert("seed".to_string(), json!(seed));
+    state.insert(
+        "rng".to_string(),
+        serde_json::to_value(GameRng::seed_from_u64(seed))?,
+    );
+
+    Ok(())
+}
+
+/// Version 4 added more product types, the business and purchases people had were all for leisure
+fn migrate_v3_to_v4(state: &mut Value) -> IncResult<()> {
+    let people = as_object_mut(state)?
+        .get_mut("people")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no people.".to_string()))?;
+
+    for person in people.values_mut() {
+        let person = as_object_mut(person)?;
+
+        let businesses_this_month = match person.remove("business_this_month") {
+            Some(Value::String(business_id)) => json!({ "Leisure": business_id }),
+            _ => json!({}),
+        };
+        person.insert("businesses_this_month".to_string(), businesses_this_month);
+
+        let purchase_days = person.remove("purchase_days").unwrap_or_else(|| json!({}));
+        person.insert(
+            "purchase_days".to_string(),
+            json!({ "Leisure": purchase_days }),
+        );
+    }
+
+    Ok(())
+}
+
+/// Version 5 added housing, homelessness used to be a flag that is now one of the ways a person is housed.
+/// Nobody had a home yet, so there are enough vacant homes for every adult to move into
+fn migrate_v4_to_v5(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let people = state
+        .get_mut("people")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no people.".to_string()))?;
+
+    let mut adult_count = 0;
+    for person in people.values_mut() {
+        let person = as_object_mut(person)?;
+
+        if person.get("age").and_then(Value::as_i64).unwrap_or(0) >= 18 {
+            adult_count += 1;
+        }
+
+        let housing = match person.remove("homeless") {
+            Some(Value::Bool(true)) => "Homeless",
+            _ => "Dependent",
+        };
+        person.insert("housing".to_string(), json!(housing));
+    }
+
+    state.insert(
+        "housing".to_string(),
+        serde_json::to_value(HousingState {
+            total_homes: adult_count,
+            market_rent: STARTING_MARKET_RENT,
+            ..HousingState::default()
+        })?,
+    );
+
+    let rules = state
+        .get_mut("rules")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no rules.".to_string()))?;
+    rules.insert(
+        "rent_cap_rule".to_string(),
+        serde_json::to_value(RentCapRule::default())?,
+    );
+    rules.insert(
+        "housing_subsidy_rule".to_string(),
+        serde_json::to_value(HousingSubsidyRule::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 6 added estates, children born before it were never linked to their mother
+fn migrate_v5_to_v6(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let people = state
+        .get_mut("people")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no people.".to_string()))?;
+
+    for person in people.values_mut() {
+        as_object_mut(person)?.insert("child_ids".to_string(), json!([]));
+    }
+
+    let rules = state
+        .get_mut("rules")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no rules.".to_string()))?;
+    rules.insert(
+        "inheritance_tax_rule".to_string(),
+        serde_json::to_value(InheritanceTaxRule::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 7 added households. Everyone lives on their own apart from children, who move in
+/// with the first parent they were linked to. Households take the id of the person they were
+/// made for
+fn migrate_v6_to_v7(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let people = state
+        .get_mut("people")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no people.".to_string()))?;
+
+    // Parents are only known from the children they were linked to
+    let mut parent_ids: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (id, person) in people.iter() {
+        let child_ids = person.get("child_ids").and_then(Value::as_array);
+
+        for child_id in child_ids.into_iter().flatten().filter_map(Value::as_str) {
+            parent_ids
+                .entry(child_id.to_string())
+                .or_default()
+                .push(id.clone());
+        }
+    }
+
+    let mut households = Map::new();
+    for (id, person) in people.iter_mut() {
+        let person = as_object_mut(person)?;
+        let parents = parent_ids.remove(id).unwrap_or_default();
+        let is_child = person.get("age").and_then(Value::as_i64).unwrap_or(0) < 18;
+
+        let household_id = match parents.first() {
+            Some(parent_id) if is_child => parent_id.clone(),
+            _ => id.clone(),
+        };
+
+        let household = households
+            .entry(household_id.clone())
+            .or_insert_with(|| json!({ "id": household_id, "member_ids": [] }));
+        if let Some(member_ids) = household["member_ids"].as_array_mut() {
+            member_ids.push(json!(id));
+        }
+
+        person.insert("household_id".to_string(), json!(household_id));
+        person.insert("partner_id".to_string(), Value::Null);
+        person.insert("parent_ids".to_string(), json!(parents));
+        person.insert("neglected".to_string(), json!(false));
+    }
+
+    state.insert("households".to_string(), Value::Object(households));
+
+    Ok(())
+}
+
+/// Version 8 added bankruptcy, businesses already in debt start their grace period now
+fn migrate_v7_to_v8(state: &mut Value) -> IncResult<()> {
+    let businesses = as_object_mut(state)?
+        .get_mut("businesses")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no businesses.".to_string()))?;
+
+    for business in businesses.values_mut() {
+        as_object_mut(business)?.insert("months_in_debt".to_string(), json!(0));
+    }
+
+    Ok(())
+}
+
+/// Version 9 added the labour market, nobody has posted a job yet
+fn migrate_v8_to_v9(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let businesses = state
+        .get_mut("businesses")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no businesses.".to_string()))?;
+
+    for business in businesses.values_mut() {
+        as_object_mut(business)?.insert("vacancies".to_string(), json!(0));
+    }
+
+    state.insert(
+        "labour_market".to_string(),
+        serde_json::to_value(LabourMarketState::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 10 added education. Nobody is in higher education yet, and schools are funded as
+/// they expect for the children already at school
+fn migrate_v9_to_v10(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let people = state
+        .get_mut("people")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no people.".to_string()))?;
+
+    let mut pupil_count = 0;
+    for person in people.values_mut() {
+        let person = as_object_mut(person)?;
+
+        let age = person.get("age").and_then(Value::as_i64).unwrap_or(0);
+        if (SCHOOL_STARTING_AGE as i64..18).contains(&age) {
+            pupil_count += 1;
+        }
+
+        person.insert("studying_for".to_string(), Value::Null);
+    }
+
+    state.insert(
+        "education".to_string(),
+        serde_json::to_value(EducationState {
+            budget: pupil_count * EXPECTED_SPENDING_PER_PUPIL,
+            ..EducationState::default()
+        })?,
+    );
+
+    let rules = state
+        .get_mut("rules")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no rules.".to_string()))?;
+    rules.insert(
+        "tuition_rule".to_string(),
+        serde_json::to_value(TuitionRule::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 11 added pensions. Careers so far are assumed to have been at the current salary,
+/// and the pension budget covers what retirees are already paid
+fn migrate_v10_to_v11(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let people = state
+        .get_mut("people")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no people.".to_string()))?;
+
+    let mut pensions = PensionState::default();
+    for person in people.values_mut() {
+        let person = as_object_mut(person)?;
+
+        let age = person.get("age").and_then(Value::as_i64).unwrap_or(0);
+        let salary = person.get("salary").and_then(Value::as_i64).unwrap_or(0);
+
+        if person.get("job").and_then(Value::as_str) == Some("Retired") {
+            pensions.budget += salary / 12;
+            pensions.retiree_count += 1;
+        }
+
+        person.insert("career_salary".to_string(), json!(salary));
+        person.insert("career_months".to_string(), json!((age - 18).max(0) * 12));
+    }
+
+    state.insert("pensions".to_string(), serde_json::to_value(pensions)?);
+
+    let rules = state
+        .get_mut("rules")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no rules.".to_string()))?;
+    rules.insert(
+        "pension_rule".to_string(),
+        serde_json::to_value(PensionRule::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 12 added interest, credit scores and more types of debt. Education debt starts
+/// charging interest, and mortgages become debts at the payments already agreed, which included
+/// their interest
+fn migrate_v11_to_v12(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let people = state
+        .get_mut("people")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no people.".to_string()))?;
+
+    for person in people.values_mut() {
+        let person = as_object_mut(person)?;
+
+        let mut debts = match person.remove("debts") {
+            Some(Value::Array(debts)) => debts,
+            _ => Vec::new(),
+        };
+        for debt in debts.iter_mut() {
+            let debt = as_object_mut(debt)?;
+            debt.insert(
+                "interest_rate".to_string(),
+                json!(DebtType::Education.get_interest_rate(STARTING_CREDIT_SCORE)),
+            );
+            debt.insert("missed_payments".to_string(), json!(0));
+        }
+
+        let mortgage = person
+            .get("housing")
+            .and_then(|housing| housing.get("Owned"))
+            .cloned();
+        if let Some(mortgage) = mortgage {
+            let payment = mortgage
+                .get("mortgage_payment")
+                .and_then(Value::as_i64)
+                .unwrap_or(0);
+            let months_left = mortgage
+                .get("months_left")
+                .and_then(Value::as_i64)
+                .unwrap_or(0);
+            let missed_payments = mortgage
+                .get("missed_payments")
+                .and_then(Value::as_i64)
+                .unwrap_or(0);
+
+            if payment > 0 && months_left > 0 {
+                debts.push(json!({
+                    "owed": (payment * months_left) as f32,
+                    "minimum_monthly_payoff": payment as f32,
+                    "debt_type": DebtType::Mortgage,
+                    "interest_rate": 0.,
+                    "missed_payments": missed_payments,
+                }));
+            }
+            person.insert("housing".to_string(), json!("Owned"));
+        }
+
+        person.insert("debts".to_string(), Value::Array(debts));
+        person.insert("credit_score".to_string(), json!(STARTING_CREDIT_SCORE));
+    }
+
+    state.insert(
+        "household_debt".to_string(),
+        serde_json::to_value(HouseholdDebtState::default())?,
+    );
+    state.insert(
+        "household_debt_graph_data".to_string(),
+        serde_json::to_value(SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA))?,
+    );
+
+    Ok(())
+}
+
+/// Version 13 added hospital charges, which start switched off so healthcare stays free
+fn migrate_v12_to_v13(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+
+    let healthcare = state
+        .get_mut("healthcare")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no healthcare.".to_string()))?;
+    healthcare.insert("charges_paid_last_month".to_string(), json!(0));
+    healthcare.insert("charges_unpaid_last_month".to_string(), json!(0));
+
+    let rules = state
+        .get_mut("rules")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no rules.".to_string()))?;
+    rules.insert(
+        "hospital_charges_rule".to_string(),
+        serde_json::to_value(HospitalChargesRule::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 14 added infectious disease, nobody has caught it yet
+fn migrate_v13_to_v14(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let people = state
+        .get_mut("people")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no people.".to_string()))?;
+
+    for person in people.values_mut() {
+        as_object_mut(person)?.insert("infection".to_string(), json!("Susceptible"));
+    }
+
+    state.insert(
+        "epidemic".to_string(),
+        serde_json::to_value(EpidemicState::default())?,
+    );
+    state.insert(
+        "infected_graph_data".to_string(),
+        serde_json::to_value(SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA))?,
+    );
+
+    let rules = state
+        .get_mut("rules")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no rules.".to_string()))?;
+    rules.insert(
+        "quarantine_rule".to_string(),
+        serde_json::to_value(QuarantineRule::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 15 added tax brackets, older saves keep their flat tax with no allowance or brackets
+fn migrate_v14_to_v15(state: &mut Value) -> IncResult<()> {
+    let income_tax = IncomeTaxState {
+        personal_allowance: 0,
+        brackets: Vec::new(),
+        revenue_by_band: vec![0],
+    };
+    as_object_mut(state)?.insert("income_tax".to_string(), serde_json::to_value(income_tax)?);
+
+    Ok(())
+}
+
+/// Version 16 added sales tax, nothing has been collected yet
+fn migrate_v15_to_v16(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    state
+        .get_mut("finance_data")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no finance data.".to_string()))?
+        .insert("sales_tax_last_month".to_string(), json!(0));
+
+    let rules = state
+        .get_mut("rules")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no rules.".to_string()))?;
+    rules.insert(
+        "sales_tax_rule".to_string(),
+        serde_json::to_value(SalesTaxRule::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 17 added public debt, the government has not borrowed yet
+fn migrate_v16_to_v17(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    state.insert(
+        "public_debt".to_string(),
+        serde_json::to_value(PublicDebtState::default())?,
+    );
+    state.insert(
+        "public_debt_graph_data".to_string(),
+        serde_json::to_value(SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA))?,
+    );
+
+    Ok(())
+}
+
+/// Version 18 added changing prices, they are first measured at the end of the current month
+fn migrate_v17_to_v18(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    let businesses = state
+        .get_mut("businesses")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no businesses.".to_string()))?;
+
+    for business in businesses.values_mut() {
+        let business = as_object_mut(business)?;
+        business.insert("units_sold_this_month".to_string(), json!(0));
+        business.insert("units_missed_this_month".to_string(), json!(0));
+    }
+
+    state.insert(
+        "prices".to_string(),
+        serde_json::to_value(PriceState::default())?,
+    );
+    state.insert(
+        "inflation_graph_data".to_string(),
+        serde_json::to_value(SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA))?,
+    );
+
+    Ok(())
+}
+
+/// Version 19 added the minimum wage, nobody has been laid off because of it yet
+fn migrate_v18_to_v19(state: &mut Value) -> IncResult<()> {
+    let state = as_object_mut(state)?;
+    state
+        .get_mut("labour_market")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no labour market.".to_string()))?
+        .insert("minimum_wage_layoffs_last_month".to_string(), json!(0));
+
+    let rules = state
+        .get_mut("rules")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no rules.".to_string()))?;
+    rules.insert(
+        "minimum_wage_rule".to_string(),
+        serde_json::to_value(MinimumWageRule::default())?,
+    );
+
+    Ok(())
+}
+
+/// Version 20 carries price changes too small to make, none have been carried yet
+fn migrate_v19_to_v20(state: &mut Value) -> IncResult<()> {
+    let businesses = as_object_mut(state)?
+        .get_mut("businesses")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no businesses.".to_string()))?;
+
+    for business in businesses.values_mut() {
+        as_object_mut(business)?.insert("price_change_carried".to_string(), json!(0.));
+    }
+
+    Ok(())
+}
+
+/// Version 21 measures prices by each business's own price changes, the fixed basket is no longer kept
+fn migrate_v20_to_v21(state: &mut Value) -> IncResult<()> {
+    let prices = as_object_mut(state)?
+        .get_mut("prices")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| Error::Danger("The saved game state has no prices.".to_string()))?;
+    prices.remove("base_prices");
+    prices.remove("basket");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::errors::Severity;
+
+    const FIXTURES: [(u32, &str); SAVE_FORMAT_VERSION as usize] = [
+        (1, include_str!("../../fixtures/saves/v1.json")),
+        (2, include_str!("../../fixtures/saves/v2.json")),
+        (3, include_str!("../../fixtures/saves/v3.json")),
+        (4, include_str!("../../fixtures/saves/v4.json")),
+        (5, include_str!("../../fixtures/saves/v5.json")),
+        (6, include_str!("../../fixtures/saves/v6.json")),
+        (7, include_str!("../../fixtures/saves/v7.json")),
+        (8, include_str!("../../fixtures/saves/v8.json")),
+        (9, include_str!("../../fixtures/saves/v9.json")),
+        (10, include_str!("../../fixtures/saves/v10.json")),
+        (11, include_str!("../../fixtures/saves/v11.json")),
+        (12, include_str!("../../fixtures/saves/v12.json")),
+        (13, include_str!("../../fixtures/saves/v13.json")),
+        (14, include_str!("../../fixtures/saves/v14.json")),
+        (15, include_str!("../../fixtures/saves/v15.json")),
+        (16, include_str!("../../fixtures/saves/v16.json")),
+        (17, include_str!("../../fixtures/saves/v17.json")),
+        (18, include_str!("../../fixtures/saves/v18.json")),
+        (19, include_str!("../../fixtures/saves/v19.json")),
+        (20, include_str!("../../fixtures/saves/v20.json")),
+        (21, include_str!("../../fixtures/saves/v21.json")),
+    ];
+
+    #[test]
+    fn loads_every_fixture_version() {
+        for (version, contents) in FIXTURES {
+            let state = deserialize_save(contents).unwrap_or_else(|err| {
+                panic!("Failed to load version {} fixture: {}", version, err)
+            });
+
+            assert_eq!(state.people.len(), 2, "version {}", version);
+            assert_eq!(state.businesses.len(), 1, "version {}", version);
+        }
+    }
+
+    #[test]
+    fn moves_mortgages_into_debts() {
+        let (_, mut state) = split_save(serde_json::from_str(FIXTURES[10].1).unwrap()).unwrap();
+        let person = state["people"]
+            .as_object_mut()
+            .unwrap()
+            .values_mut()
+            .next()
+            .unwrap();
+        person["housing"] = json!({
+            "Owned": { "mortgage_payment": 400, "months_left": 10, "missed_payments": 1 }
+        });
+        let id: uuid::Uuid = person["id"].as_str().unwrap().parse().unwrap();
+
+        migrate(11, &mut state).unwrap();
+        let state: GameState = serde_json::from_value(state).unwrap();
+
+        let person = &state.people[&id];
+        let mortgage = person
+            .debts
+            .iter()
+            .find(|debt| debt.debt_type == DebtType::Mortgage)
+            .unwrap();
+        assert_eq!(mortgage.owed, 4000.);
+        assert_eq!(mortgage.missed_payments, 1);
+        assert_eq!(person.credit_score, STARTING_CREDIT_SCORE);
+    }
+
+    #[test]
+    fn round_trips_the_current_version() {
+        let contents = serialize_save(&GameState::default()).unwrap();
+        let state = deserialize_save(&contents).unwrap();
+
+        assert_eq!(
+            state.government_balance,
+            GameState::default().government_balance
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        let contents = format!(
+            r#"{{ "header": {{ "version": {} }}, "state": {{}} }}"#,
+            SAVE_FORMAT_VERSION + 1
+        );
+
+        let err = deserialize_save(&contents).err().unwrap();
+        assert_eq!(err.severity(), Severity::Danger as u8);
+    }
+}
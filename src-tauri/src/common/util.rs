@@ -1,9 +1,8 @@
 use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::{
-    collections::HashMap,
-    ops::{Index, IndexMut},
-};
+use std::ops::{Index, IndexMut};
+use uuid::{Builder, Uuid};
 
 use crate::game::structs::{HealthcareGroup, HealthcareState};
 
@@ -26,19 +25,29 @@ pub fn set_decimal_count(number: f32, decimal_count: u32) -> f32 {
     (number * power).round() / power
 }
 
-pub fn generate_percentage() -> i32 {
-    let mut rng = rand::thread_rng();
+/// Every random draw in the simulation goes through this, so that a game can be reproduced from its seed
+pub type GameRng = ChaCha8Rng;
+
+/// Generates an id from the game's rng rather than the OS, so ids are the same for the same seed
+pub fn generate_id(rng: &mut GameRng) -> Uuid {
+    Builder::from_random_bytes(rng.gen()).into_uuid()
+}
+
+pub fn generate_percentage(rng: &mut GameRng) -> i32 {
     rng.gen_range(0..100)
 }
 
 /// Takes in an input of percentages and then an output is generated based on the chances provided
-pub fn percentage_based_output_int<ValueType>(chances: HashMap<ValueType, i32>) -> ValueType {
+pub fn percentage_based_output_int<ValueType>(
+    chances: Vec<(ValueType, i32)>,
+    rng: &mut GameRng,
+) -> ValueType {
     let mut remaining_percentage = 100;
-    let percentage = generate_percentage();
+    let percentage = generate_percentage(rng);
     let mut ret_value: Option<ValueType> = None;
 
-    let mut sorted_chances: Vec<_> = chances.into_iter().collect();
-    sorted_chances.sort_by(|a, b| a.1.cmp(&b.1));
+    let mut sorted_chances = chances;
+    sorted_chances.sort_by_key(|a| a.1);
 
     for (value, chance) in sorted_chances {
         remaining_percentage -= chance;
@@ -59,12 +68,15 @@ pub fn percentage_based_output_int<ValueType>(chances: HashMap<ValueType, i32>)
 }
 
 /// This isn't really a float percentage, it just rounds the float values to the nearest int value
-pub fn percentage_based_output_float<ValueType>(chances: HashMap<ValueType, f32>) -> ValueType {
+pub fn percentage_based_output_float<ValueType>(
+    chances: Vec<(ValueType, f32)>,
+    rng: &mut GameRng,
+) -> ValueType {
     let mut remaining_percentage = 100;
-    let percentage = generate_percentage();
+    let percentage = generate_percentage(rng);
     let mut ret_value: Option<ValueType> = None;
 
-    let mut sorted_chances: Vec<_> = chances.into_iter().collect();
+    let mut sorted_chances = chances;
     sorted_chances.sort_by(|a, b| a.1.total_cmp(&b.1));
 
     for (value, chance) in sorted_chances {
@@ -87,8 +99,7 @@ pub fn percentage_based_output_float<ValueType>(chances: HashMap<ValueType, f32>
 }
 
 /// Random float range
-pub fn float_range(min: f32, max: f32, decimal_count: u32) -> f32 {
-    let mut rng = rand::thread_rng();
+pub fn float_range(min: f32, max: f32, decimal_count: u32, rng: &mut GameRng) -> f32 {
     set_decimal_count(rng.gen::<f32>() * (max - min) + min, decimal_count)
 }
 
@@ -192,13 +203,13 @@ pub fn length(n: u32) -> i32 {
     count
 }
 
-pub fn percentage_chance(percent: f32) -> bool {
+pub fn percentage_chance(percent: f32, rng: &mut GameRng) -> bool {
     let maximum = (100. / percent) as i32;
-    rand::thread_rng().gen_range(0..=maximum) == maximum
+    rng.gen_range(0..=maximum) == maximum
 }
 
-pub fn chance_one_in(amount: i32) -> bool {
-    rand::thread_rng().gen_range(0..=amount) == amount
+pub fn chance_one_in(amount: i32, rng: &mut GameRng) -> bool {
+    rng.gen_range(0..=amount) == amount
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -253,6 +264,10 @@ impl<T: Default + Clone> SlotArray<T> {
     pub fn len(&self) -> usize {
         self.array.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
 }
 
 impl<T> Index<usize> for SlotArray<T> {
@@ -268,8 +283,8 @@ impl<T> IndexMut<usize> for SlotArray<T> {
     }
 }
 
-pub fn generate_unemployed_salary() -> i32 {
-    rand::thread_rng().gen_range(300..=1100)
+pub fn generate_unemployed_salary(rng: &mut GameRng) -> i32 {
+    rng.gen_range(300..=1100)
 }
 
 pub fn get_healthcare_group(age: i32, healthcare: &mut HealthcareState) -> &mut HealthcareGroup {
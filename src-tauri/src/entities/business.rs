@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::{collections::BTreeMap, ops::Range};
 
-use maplit::hashmap;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
@@ -9,11 +9,14 @@ use crate::{
     common::config::Config,
     common::{
         errors::{Error, IncResult},
-        util::{float_range, generate_unemployed_salary, percentage_based_output_int},
+        util::{
+            float_range, generate_id, generate_unemployed_salary, percentage_based_output_int,
+            GameRng,
+        },
     },
     game::{
-        generation::{generate_education_level, get_expected_salary_range},
-        structs::{BusinessFundingRule, BusinessTaxRule},
+        generation::{generate_education_level, get_expected_salary_range, get_product_config},
+        structs::{BusinessFundingRule, BusinessTaxRule, MinimumWageRule, SalesTaxRule},
     },
     percentage_of,
 };
@@ -23,16 +26,80 @@ use super::person::person::{
     Job, Person,
 };
 
-#[derive(Default, Clone, PartialEq, Eq, Hash)]
+const OWNER_PROFIT_SHARE: f64 = 0.5; // Part of last month's income paid to the owner
+const OWNER_MINIMUM_DRAW: f64 = 0.5; // Part of an employee's salary the owner takes when the business makes a loss
+pub const FOUNDER_START_CAPITAL: f32 = 0.45; // Part of the founder's balance put into a new business
+const SALARY_RAISE: f32 = 0.03; // Raise in the salary offer when job seekers turned it down
+const MAXIMUM_PRICE_CHANGE: f32 = 0.05; // Most a price moves in a month
+const TARGET_SALES_SHARE: f32 = 0.95; // Share of wanted products sold at which the price is right
+const DEMAND_PRICE_RESPONSE: f32 = 0.5; // Price change for each missed or exceeded share of sales
+const COMPETITION_PRICE_RESPONSE: f32 = 0.2; // Share of the gap to competitors' average price closed each month
+const LOSS_PRICE_RISE: f32 = 0.02; // Prices go up when the business made a loss last month
+const MINIMUM_WAGE_RESERVE_MONTHS: f64 = 6.; // Businesses keep staff they could pay this long from their balance
+const HIRING_RESERVE_MONTHS: f64 = 6.; // Businesses only hire staff they could pay this long from their balance
+
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 pub enum ProductType {
     #[default]
     Leisure,
-    // These will be implemented later:
-    // FURNITURE
-    // HOUSES
+    Food,
+    Housing,
+    Furniture,
+    HealthcareGoods,
 }
 
-#[derive(Default, Clone)]
+impl ProductType {
+    pub const ALL: [ProductType; 5] = [
+        ProductType::Leisure,
+        ProductType::Food,
+        ProductType::Housing,
+        ProductType::Furniture,
+        ProductType::HealthcareGoods,
+    ];
+
+    /// Whether the product is bought on the purchase days planned at the start of each month.
+    /// Food is bought every day with the individual's daily food spending instead, and housing is paid for
+    /// as rent to the landlords each month
+    pub fn is_bought_on_purchase_days(self) -> bool {
+        !matches!(self, ProductType::Food | ProductType::Housing)
+    }
+
+    /// How much of a person's spending goes to this product, relative to the other products
+    pub fn demand_weight(self, config: &Config, salary: i32) -> f32 {
+        let product_config = get_product_config(config, self);
+        let weight =
+            product_config.demand_weight + product_config.income_weight * (salary as f32 / 50000.);
+
+        weight.max(0.05) // Everyone needs a little of everything
+    }
+
+    /// The product that has the most demand for each business selling it, so new businesses are started there
+    pub fn most_underserved(
+        businesses: &BTreeMap<Uuid, Business>,
+        demand: &BTreeMap<ProductType, f32>,
+    ) -> ProductType {
+        let mut business_counts: BTreeMap<ProductType, usize> = BTreeMap::new();
+        for business in businesses.values() {
+            *business_counts.entry(business.product_type).or_insert(0) += 1;
+        }
+
+        ProductType::ALL
+            .into_iter()
+            .max_by(|a, b| {
+                let demand_per_business = |product_type: &ProductType| {
+                    demand.get(product_type).copied().unwrap_or(0.)
+                        / (business_counts.get(product_type).copied().unwrap_or(0) + 1) as f32
+                };
+
+                demand_per_business(a).total_cmp(&demand_per_business(b))
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Business {
     pub id: Uuid,
     pub owner_id: Uuid,
@@ -55,6 +122,12 @@ pub struct Business {
     pub last_month_income: f64,  // Income made last month
 
     pub loss_percentage: i32, // Business funds that is spent on resources
+    pub months_in_debt: i32,  // Months in a row the business has ended with no money
+    pub vacancies: i32,       // Jobs left open after last month's hiring
+
+    pub units_sold_this_month: i32,
+    pub units_missed_this_month: i32, // Wanted by customers who could not afford them
+    pub price_change_carried: f32,    // Price change too small to make yet, see adjust_price
 }
 
 impl Business {
@@ -66,6 +139,34 @@ impl Business {
         standard_tax_rate
     }
 
+    /// Rate of sales tax customers pay on top of the price of a product
+    pub fn get_sales_tax_rate(rule: &SalesTaxRule, product_type: ProductType) -> f32 {
+        if !rule.enabled {
+            return 0.;
+        }
+
+        match product_type {
+            ProductType::Leisure => rule.leisure,
+            ProductType::Food => rule.food,
+            ProductType::Housing => rule.housing,
+            ProductType::Furniture => rule.furniture,
+            ProductType::HealthcareGoods => rule.healthcare_goods,
+        }
+    }
+
+    pub fn get_minimum_wage(rule: &MinimumWageRule) -> i32 {
+        if rule.enabled {
+            return rule.minimum_wage;
+        }
+
+        0
+    }
+
+    /// Price of one product to customers, including sales tax
+    pub fn get_shelf_price(&self, sales_tax_rate: f32) -> f32 {
+        self.product_price as f32 * (1. + sales_tax_rate)
+    }
+
     pub fn check_funding(
         rule: &BusinessFundingRule,
         business: &mut Business,
@@ -84,19 +185,22 @@ impl Business {
 
 impl Business {
     /// Generates a business based on demand
+    #[allow(clippy::too_many_arguments)]
     pub fn generate(
         &mut self,
         config: &Config,
         product_type: ProductType,
         product_demand: f32,
         remaining_market_percentage: &mut f32,
-        people: &mut HashMap<Uuid, Person>,
+        people: &mut BTreeMap<Uuid, Person>,
         tax_rate: f32,
+        sales_tax_rate: f32,
+        rng: &mut GameRng,
     ) -> bool {
-        self.generate_start_values(product_type, config);
+        self.generate_start_values(product_type, config, rng);
 
         let (sufficient_businesses, marketing_reach_percentage) =
-            self.generate_marketing_reach(remaining_market_percentage);
+            self.generate_marketing_reach(remaining_market_percentage, rng);
         if sufficient_businesses {
             return true;
         }
@@ -105,6 +209,8 @@ impl Business {
             as_decimal_percent!(marketing_reach_percentage) * product_demand,
             people,
             0.8,
+            sales_tax_rate,
+            rng,
         ) as i64;
 
         // TODO: make this more varied & accurate, influence it by external factors
@@ -112,24 +218,23 @@ impl Business {
         let marketing_cost =
             as_decimal_percent!(self.marketing_cost_percentage) * self.expected_income as f32;
         // This can only be a maximum of 67%, leaving roughly 30% capacity for employees, the minimum (with tax no lower than 20%) is 40%
-        self.loss_percentage = percentage_of!(marketing_cost + production_cost as f32; / self.expected_income)
+        self.loss_percentage = percentage_of!(marketing_cost + production_cost; / self.expected_income)
             + (tax_rate * 100.) as i32;
 
-        self.employee_salary = self.generate_employee_salary(config, self.loss_percentage);
-        self.employee_budget_allocation = float_range(0.53, 0.63, 3);
+        self.employee_salary = self.generate_employee_salary(config, self.loss_percentage, rng);
+        self.employee_budget_allocation = float_range(0.53, 0.63, 3, rng);
 
         let expected_employee_count = self.calculate_expected_employee_count();
 
         let people_vec: &mut Vec<_> = &mut people
             .values_mut()
-            .into_iter()
             .filter(|per| per.job != Job::Retired)
             .collect();
         // people_vec.sort_by_cached_key(|p| p.education_level as u8);
         self.assign_employees(people_vec, expected_employee_count);
 
         self.loss_percentage += percentage_of!(self.employees.len() * (self.employee_salary as usize / 12); / self.expected_income);
-        self.set_starting_balance();
+        self.set_starting_balance(rng);
 
         false
     }
@@ -140,34 +245,44 @@ impl Business {
         product_type: ProductType,
         config: &Config,
         start_balance: f64,
+        wage_index: f32,
+        rng: &mut GameRng,
     ) {
-        self.generate_start_values(product_type, config);
+        self.generate_start_values(product_type, config, rng);
 
-        self.employee_salary = rand::thread_rng().gen_range(get_expected_salary_range(
+        let employee_salary = rng.gen_range(get_expected_salary_range(
             config,
             &self.minimum_education_level,
         ));
-        self.employee_budget_allocation = float_range(0.53, 0.63, 3);
+        self.employee_salary = (employee_salary as f32 * wage_index) as i32;
+        self.employee_budget_allocation = float_range(0.53, 0.63, 3, rng);
 
         self.balance = start_balance;
         self.last_month_balance = self.balance;
     }
 
-    pub fn generate_start_values(&mut self, product_type: ProductType, config: &Config) {
-        self.id = Uuid::new_v4();
-        let mut rng = rand::thread_rng();
+    pub fn generate_start_values(
+        &mut self,
+        product_type: ProductType,
+        config: &Config,
+        rng: &mut GameRng,
+    ) {
+        self.id = generate_id(rng);
+
+        let price_range = &get_product_config(config, product_type).price_range;
 
         self.product_type = product_type;
-        self.minimum_education_level = generate_education_level(config);
+        self.minimum_education_level = generate_education_level(config, rng);
         self.marketing_cost_percentage = rng.gen_range(1..=2);
-        self.product_price = rng.gen_range(2..150); // TODO: determine this price more accurately?
-        self.production_cost_per_product = self.product_price as f32 * float_range(0.03, 0.05, 3);
+        self.product_price = rng.gen_range(price_range.min..price_range.max); // Only a starting point, see adjust_price
+        self.production_cost_per_product =
+            self.product_price as f32 * float_range(0.03, 0.05, 3, rng);
     }
 
-    fn set_starting_balance(&mut self) {
+    fn set_starting_balance(&mut self, rng: &mut GameRng) {
         let expected_income = self.expected_income as f64;
 
-        self.balance = expected_income * float_range(0.15, 3., 3) as f64; // A range of 150% - 300% of the expected profit is the business balance
+        self.balance = expected_income * float_range(0.15, 3., 3, rng) as f64; // A range of 150% - 300% of the expected profit is the business balance
         self.balance -= expected_income * as_decimal_percent!(self.loss_percentage) as f64;
         self.last_month_balance = self.balance;
     }
@@ -178,7 +293,12 @@ impl Business {
         (budget_allocation as f32 / (self.employee_salary as f32 / 12.)) as i32
     }
 
-    fn generate_employee_salary(&self, config: &Config, loss_percentage: i32) -> i32 {
+    fn generate_employee_salary(
+        &self,
+        config: &Config,
+        loss_percentage: i32,
+        rng: &mut GameRng,
+    ) -> i32 {
         let expected_salary_range =
             get_expected_salary_range(config, &self.minimum_education_level);
 
@@ -195,22 +315,24 @@ impl Business {
             _ => expected_salary_range.start..lower_mid_of_range,
         };
 
-        rand::thread_rng().gen_range(employee_salary_range)
+        rng.gen_range(employee_salary_range)
     }
 
     pub fn get_production_cost(&self) -> f32 {
-        (self.expected_income / self.product_price as i64) as f32
-            * self.production_cost_per_product as f32
+        (self.expected_income / self.product_price as i64) as f32 * self.production_cost_per_product
     }
 
-    fn generate_marketing_reach(&self, remaining_market_percentage: &mut f32) -> (bool, f32) {
-        let marketing_reach_percentage =
-            match self.minimum_education_level {
-                NoFormalEducation | HighSchoolDiploma | College => self
-                    .random_marketing_percentage_multiplyer(remaining_market_percentage, 0.9, 1.5),
-                AssociateDegree | Bachelors | AdvancedDegree => self
-                    .random_marketing_percentage_multiplyer(remaining_market_percentage, 1.2, 2.8),
-            };
+    fn generate_marketing_reach(
+        &self,
+        remaining_market_percentage: &mut f32,
+        rng: &mut GameRng,
+    ) -> (bool, f32) {
+        let marketing_reach_percentage = match self.minimum_education_level {
+            NoFormalEducation | HighSchoolDiploma | College => self
+                .random_marketing_percentage_multiplyer(remaining_market_percentage, 0.9, 1.5, rng),
+            AssociateDegree | Bachelors | AdvancedDegree => self
+                .random_marketing_percentage_multiplyer(remaining_market_percentage, 1.2, 2.8, rng),
+        };
 
         *remaining_market_percentage -= marketing_reach_percentage;
         if *remaining_market_percentage <= 0. {
@@ -223,11 +345,11 @@ impl Business {
     pub fn assign_to_people(
         &self,
         demand: f32,
-        people: &mut HashMap<Uuid, Person>,
+        people: &mut BTreeMap<Uuid, Person>,
         purchase_rate: f32,
+        sales_tax_rate: f32,
+        rng: &mut GameRng,
     ) -> i32 {
-        let mut rng = rand::thread_rng();
-
         // People who have not yet picked a business to buy from
         let mut met_demand = 0.;
 
@@ -235,22 +357,36 @@ impl Business {
             if met_demand >= demand {
                 break;
             }
-            if person.business_this_month.is_some() {
+            if person
+                .businesses_this_month
+                .contains_key(&self.product_type)
+            {
+                continue;
+            }
+
+            person
+                .businesses_this_month
+                .insert(self.product_type, self.id);
+            let person_demand = person.demand.get(&self.product_type).copied().unwrap_or(0.);
+
+            if !self.product_type.is_bought_on_purchase_days() {
+                met_demand += person_demand;
                 continue;
             }
 
-            person.business_this_month = Some(self.id);
-            let person_demand = person.demand[&self.product_type];
-            let purchase_capacity = person_demand as i32 / self.product_price;
-            met_demand += (purchase_capacity * self.product_price) as f32;
+            // People budget for the price they pay, so sales tax leaves them able to buy less
+            let shelf_price = self.get_shelf_price(sales_tax_rate);
+            let purchase_capacity = (person_demand / shelf_price) as i32;
+            met_demand += purchase_capacity as f32 * shelf_price;
 
+            let purchase_days = person.purchase_days.entry(self.product_type).or_default();
             for _ in 0..purchase_capacity {
                 let day = rng.gen_range(1..=30);
-                *person.purchase_days.entry(day).or_insert(1) += 1;
+                *purchase_days.entry(day).or_insert(1) += 1;
             }
         }
 
-        (met_demand as f32 * purchase_rate) as i32 // Expect roughly 5% of people not afford items
+        (met_demand * purchase_rate) as i32 // Expect roughly 5% of people not afford items
     }
 
     fn assign_employees(&mut self, unemployed_people: &mut [&mut Person], new_employee_count: i32) {
@@ -286,23 +422,19 @@ impl Business {
         remaining_market_percentage: &mut f32,
         min: f32,
         max: f32,
+        rng: &mut GameRng,
     ) -> f32 {
         // 1 - smallest, 3 - largest
-        let tier = percentage_based_output_int(hashmap! {
-            1 => 82,
-            2 => 15,
-            3 => 3,
-        });
+        let tier = percentage_based_output_int(vec![(1, 82), (2, 15), (3, 3)], rng);
 
-        let mut rng = rand::thread_rng();
         let increase_multiplyer = match tier {
-            2 => float_range(0.5, 2., 2),
+            2 => float_range(0.5, 2., 2, rng),
             3 => rng.gen_range(3..5) as f32,
             _ => 1.,
         };
 
         let market_percentage =
-            float_range(min * increase_multiplyer, max * increase_multiplyer, 2);
+            float_range(min * increase_multiplyer, max * increase_multiplyer, 2, rng);
         if market_percentage > *remaining_market_percentage {
             return *remaining_market_percentage;
         }
@@ -311,27 +443,30 @@ impl Business {
     }
 
     /// This function assigns the business to a new market with a new market percentage. This runs monthly.
+    #[allow(clippy::too_many_arguments)]
     pub fn get_new_market(
         &mut self,
         market_percentage: f32,
         cost_per_percent: f32,
-        people: &mut HashMap<Uuid, Person>,
-        unemployed_people: &mut Vec<&mut Person>,
+        people: &mut BTreeMap<Uuid, Person>,
         demand: f32,
         purchase_rate: f32,
+        sales_tax_rate: f32,
+        rng: &mut GameRng,
     ) -> IncResult<()> {
         self.expected_income = self.assign_to_people(
             as_decimal_percent!(market_percentage) * demand,
             people,
             purchase_rate,
+            sales_tax_rate,
+            rng,
         ) as i64
             * self.product_price as i64;
         let employee_diff = self.calculate_expected_employee_count() - self.employees.len() as i32;
 
-        if employee_diff > 0 && !unemployed_people.is_empty() {
-            self.assign_employees(unemployed_people, employee_diff);
-        } else if employee_diff < 0 {
-            self.remove_employees(employee_diff, people)?;
+        // New staff are hired through the labour market
+        if employee_diff < 0 {
+            self.remove_employees(employee_diff, people, rng)?;
         }
 
         self.balance -=
@@ -339,10 +474,120 @@ impl Business {
         Ok(())
     }
 
+    /// Runs monthly. The price goes up when customers could afford nearly everything they wanted or when the
+    /// business made a loss, and down when many could not afford it. Competitors pull it towards their average price
+    pub fn adjust_price(&mut self, competitor_price: Option<f32>) {
+        let units_wanted = self.units_sold_this_month + self.units_missed_this_month;
+        let demand_change = match units_wanted {
+            0 => 0.,
+            wanted => {
+                (self.units_sold_this_month as f32 / wanted as f32 - TARGET_SALES_SHARE)
+                    * DEMAND_PRICE_RESPONSE
+            }
+        };
+
+        let cost_change = if self.last_month_income < 0. {
+            LOSS_PRICE_RISE
+        } else {
+            0.
+        };
+
+        // A gap of one step can not be closed without overtaking competitors, who would then step back
+        let competition_change = match competitor_price {
+            Some(competitor_price) if (competitor_price - self.product_price as f32).abs() > 1. => {
+                (competitor_price / self.product_price as f32 - 1.) * COMPETITION_PRICE_RESPONSE
+            }
+            _ => 0.,
+        };
+
+        let change = (demand_change + cost_change + competition_change)
+            .clamp(-MAXIMUM_PRICE_CHANGE, MAXIMUM_PRICE_CHANGE);
+        // Cheap products can not move by a fraction, so the change is carried over until it adds up to a whole step
+        let wanted_change = self.product_price as f32 * change + self.price_change_carried;
+        let price_step = wanted_change.trunc();
+        self.price_change_carried = wanted_change - price_step;
+        self.product_price = (self.product_price + price_step as i32).max(1);
+
+        self.units_sold_this_month = 0;
+        self.units_missed_this_month = 0;
+    }
+
+    /// Raises pay to the minimum wage. Staff are kept if the business's expected income or savings can pay them,
+    /// the rest are let go. Returns how many lost their job
+    pub fn pay_minimum_wage(
+        &mut self,
+        minimum_wage: i32,
+        people: &mut BTreeMap<Uuid, Person>,
+        rng: &mut GameRng,
+    ) -> IncResult<i32> {
+        if self.employee_salary >= minimum_wage {
+            return Ok(0);
+        }
+
+        self.employee_salary = minimum_wage;
+        for emp_id in &self.employees {
+            if let Some(emp) = people.get_mut(emp_id) {
+                emp.set_salary(minimum_wage);
+            }
+        }
+
+        let monthly_salary = minimum_wage as f64 / 12.;
+        let affordable_from_balance =
+            (self.balance.max(0.) / (monthly_salary * MINIMUM_WAGE_RESERVE_MONTHS)) as i32;
+        let affordable = self
+            .calculate_expected_employee_count()
+            .max(affordable_from_balance);
+
+        let layoffs = (self.employees.len() as i32 - affordable).max(0);
+        self.remove_employees(layoffs, people, rng)?;
+
+        Ok(layoffs)
+    }
+
+    /// Salaries expected for the education the business asks for, following prices
+    fn get_offered_salary_range(&self, config: &Config, wage_index: f32) -> Range<i32> {
+        let salary_range = get_expected_salary_range(config, &self.minimum_education_level);
+        (salary_range.start as f32 * wage_index) as i32
+            ..(salary_range.end as f32 * wage_index) as i32
+    }
+
+    /// Brings the salary offer up to the range expected for the education the business asks for and the minimum
+    /// wage, pay is never cut. Jobs are opened for the staff the business expects to need, as long as it could pay
+    /// them from its balance and there are job seekers able to fill them. Returns how many jobs are open
+    pub fn post_vacancies(
+        &mut self,
+        config: &Config,
+        wage_index: f32,
+        minimum_wage: i32,
+        labour_pool: i32,
+    ) -> i32 {
+        let salary_range = self.get_offered_salary_range(config, wage_index);
+        self.employee_salary = self
+            .employee_salary
+            .max(salary_range.start)
+            .max(minimum_wage);
+
+        let monthly_salary = self.employee_salary as f64 / 12.;
+        let affordable = (self.balance.max(0.) / (monthly_salary * HIRING_RESERVE_MONTHS)) as i32;
+        let wanted = self.calculate_expected_employee_count().min(affordable);
+
+        self.vacancies = (wanted - self.employees.len() as i32).clamp(0, labour_pool.max(0));
+        self.vacancies
+    }
+
+    /// Runs when job seekers able to fill the business's open jobs turned the salary offer down. The offer goes up,
+    /// but not past the range expected for the education the business asks for
+    pub fn raise_salary_offer(&mut self, config: &Config, wage_index: f32) {
+        let salary_range = self.get_offered_salary_range(config, wage_index);
+        let salary = (self.employee_salary as f32 * (1. + SALARY_RAISE)) as i32;
+        self.employee_salary = salary.min(salary_range.end).max(self.employee_salary);
+    }
+
     pub fn remove_employees(
         &mut self,
         amount: i32,
-        people: &mut HashMap<Uuid, Person>,
+        people: &mut BTreeMap<Uuid, Person>,
+        rng: &mut GameRng,
     ) -> IncResult<()> {
         // Sort employees by lowest welfare to highest
 
@@ -374,30 +619,189 @@ impl Business {
                 Error::Warning(format!("Could not find person with id {}", per_id))
             })?;
             per.job = Job::Unemployed;
-            per.set_salary(generate_unemployed_salary());
+            per.set_salary(generate_unemployed_salary(rng));
         }
 
         Ok(())
     }
 
-    /// This is run on a monthly basis
-    // pub fn pay_owner(&mut self, owner: &mut Person) {
-    //     let month_profits = self.balance - self.last_month_balance; // The profit percentage earned in the current month
+    /// What the owner takes from the business this month, a share of last month's income or a
+    /// minimum draw when it made a loss. Never more than the business has
+    pub fn get_owner_draw(&self) -> f64 {
+        let minimum_draw = (self.employee_salary as f64 / 12.) * OWNER_MINIMUM_DRAW;
+        let draw = (self.last_month_income * OWNER_PROFIT_SHARE).max(minimum_draw);
 
-    //     let owner_expected_income = month_profits / 2.;
-    //     if owner_expected_income < (self.employee_salary as f64 / 12.) {
-    //         owner.business_pay(self, self.employee_salary as f64);
-    //         return;
-    //     }
+        draw.min(self.balance.max(0.))
+    }
+
+    /// This is run on a monthly basis, returns what the owner was paid
+    pub fn pay_owner(&mut self, owner: &mut Person) -> f64 {
+        let draw = self.get_owner_draw();
+        owner.business_pay(self, draw);
 
-    //     owner.business_pay(self, owner_expected_income);
-    // }
+        draw
+    }
 
     pub fn pay_tax(&mut self, government_balance: &mut i64, amount: f64) {
         if amount <= 0. {
             return;
         }
-        self.balance -= amount as f64;
+        self.balance -= amount;
         *government_balance += amount as i64;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn businesses_selling(product_types: &[ProductType]) -> BTreeMap<Uuid, Business> {
+        product_types
+            .iter()
+            .map(|&product_type| {
+                let business = Business {
+                    id: Uuid::new_v4(),
+                    product_type,
+                    ..Business::default()
+                };
+                (business.id, business)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn new_businesses_go_where_demand_is_least_served() {
+        let demand = BTreeMap::from([
+            (ProductType::Leisure, 3000.),
+            (ProductType::Food, 2000.),
+            (ProductType::Furniture, 500.),
+        ]);
+
+        assert_eq!(
+            ProductType::most_underserved(&BTreeMap::new(), &demand),
+            ProductType::Leisure
+        );
+
+        // Three leisure businesses share their demand, a single food business does not
+        let businesses = businesses_selling(&[
+            ProductType::Leisure,
+            ProductType::Leisure,
+            ProductType::Leisure,
+        ]);
+        assert_eq!(
+            ProductType::most_underserved(&businesses, &demand),
+            ProductType::Food
+        );
+    }
+
+    #[test]
+    fn owner_draws_a_share_of_profit_or_a_minimum_on_a_loss() {
+        let mut business = Business {
+            employee_salary: 24000,
+            balance: 10000.,
+            last_month_income: 6000.,
+            ..Business::default()
+        };
+        let mut owner = Person::default();
+
+        assert_eq!(business.pay_owner(&mut owner), 6000. * OWNER_PROFIT_SHARE);
+        assert_eq!(owner.balance, 3000.);
+        assert_eq!(business.balance, 7000.);
+
+        business.last_month_income = -5000.;
+        let minimum_draw = 2000. * OWNER_MINIMUM_DRAW;
+        assert_eq!(business.get_owner_draw(), minimum_draw);
+
+        // Never more than the business has left
+        business.balance = 300.;
+        assert_eq!(business.get_owner_draw(), 300.);
+        business.balance = -300.;
+        assert_eq!(business.get_owner_draw(), 0.);
+    }
+
+    #[test]
+    fn cheap_products_change_price_a_step_at_a_time() {
+        let mut business = Business {
+            product_price: 5,
+            units_sold_this_month: 10,
+            units_missed_this_month: 90,
+            ..Business::default()
+        };
+
+        // Most customers could not afford it, a 5% cut adds up to a whole step after four months
+        for _ in 0..3 {
+            business.adjust_price(None);
+            assert_eq!(business.product_price, 5);
+            business.units_sold_this_month = 10;
+            business.units_missed_this_month = 90;
+        }
+        business.adjust_price(None);
+        assert_eq!(business.product_price, 4);
+        assert_eq!(business.units_sold_this_month, 0);
+        assert_eq!(business.units_missed_this_month, 0);
+
+        business.product_price = 1;
+        for _ in 0..40 {
+            business.adjust_price(Some(0.5));
+            assert_eq!(business.product_price, 1);
+        }
+    }
+
+    #[test]
+    fn expensive_products_stay_within_the_price_cap() {
+        let mut business = Business {
+            product_price: 200,
+            units_sold_this_month: 10,
+            units_missed_this_month: 90,
+            ..Business::default()
+        };
+
+        business.adjust_price(Some(100.));
+        assert_eq!(business.product_price, 190);
+    }
+
+    #[test]
+    fn price_stays_when_nothing_changes() {
+        let mut business = Business {
+            product_price: 5,
+            units_sold_this_month: 95,
+            units_missed_this_month: 5,
+            ..Business::default()
+        };
+
+        business.adjust_price(Some(5.));
+        business.adjust_price(None);
+        assert_eq!(business.product_price, 5);
+    }
+
+    #[test]
+    fn competing_sellers_do_not_swap_prices() {
+        for (cheap_price, dear_price) in [(5, 6), (5, 9)] {
+            let mut cheap = Business {
+                product_price: cheap_price,
+                ..Business::default()
+            };
+            let mut dear = Business {
+                product_price: dear_price,
+                ..Business::default()
+            };
+
+            for _ in 0..24 {
+                for business in [&mut cheap, &mut dear] {
+                    business.units_sold_this_month = 95;
+                    business.units_missed_this_month = 5;
+                }
+                let (cheap_price, dear_price) = (cheap.product_price, dear.product_price);
+                cheap.adjust_price(Some(dear_price as f32));
+                dear.adjust_price(Some(cheap_price as f32));
+
+                assert!(cheap.product_price < dear.product_price);
+                assert!((cheap.product_price - cheap_price).abs() <= 1);
+                assert!((dear.product_price - dear_price).abs() <= 1);
+            }
+
+            // They close in on each other and stay a step apart
+            assert_eq!(dear.product_price - cheap.product_price, 1);
+        }
+    }
+}
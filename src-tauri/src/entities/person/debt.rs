@@ -1,55 +1,246 @@
 use std::ops::Range;
 
-use crate::common::util::percentage_based_output_int;
+use crate::{
+    common::util::{float_range, percentage_based_output_int, percentage_chance, GameRng},
+    game::structs::{HouseholdDebtState, HousingState},
+};
 
-use super::person::{EducationLevel::*, Person, SpendingBehaviour};
-use maplit::hashmap;
+use super::{
+    housing::Housing,
+    person::{EducationLevel, EducationLevel::*, Person, SpendingBehaviour},
+};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 const US_DEBT_REPAYMENT_THRESHOLD: f32 = 32_000.; // Minimum salary required to start paying debts
+const MISSED_PAYMENTS_BEFORE_DEFAULT: i32 = 3;
+pub const STARTING_CREDIT_SCORE: i32 = 70; // Out of 100
+pub const POOR_CREDIT_SCORE: i32 = 30; // Lenders and landlords turn away anyone below this
+pub const MORTGAGE_CREDIT_SCORE: i32 = 60;
+const ON_TIME_CREDIT_GAIN: i32 = 1; // Each month every payment is made
+const MISSED_PAYMENT_CREDIT_LOSS: i32 = 10;
+const DEFAULT_CREDIT_LOSS: i32 = 40;
+const CREDIT_RISK_PREMIUM: f32 = 0.18; // Extra yearly interest charged to those with no credit score at all
+const CONSUMER_CREDIT_MONTHS: i32 = 24;
+const MEDICAL_DEBT_MONTHS: i32 = 36;
+const STARTING_MEDICAL_DEBT_CHANCE: f32 = 5.;
 
 impl Person {
     pub fn get_monthly_debt_cost(&mut self) {
         let mut total = 0.;
 
         for debt in self.debts.iter() {
-            if Debt::required_to_pay(self) {
-                total += (self.salary / 12) as f32 * (debt.minimum_monthly_payoff / 100.);
+            if debt.debt_type != DebtType::Education || Debt::required_to_pay(self) {
+                total += debt.get_payment(self.salary);
             }
         }
 
         self.monthly_debt_cost = total;
     }
+
+    /// Adds a month of interest to the individual's debts and pays what is due. Debts left unpaid
+    /// for too long are defaulted on and written off, and a defaulted mortgage costs the individual
+    /// their home. Anyone left overdrawn then borrows the shortfall if lenders will still let them
+    pub fn pay_debts(&mut self, housing: &mut HousingState, debt_state: &mut HouseholdDebtState) {
+        let salary = self.salary;
+        // Education debt waits until the individual has finished studying and earns enough
+        let paying_education = Debt::required_to_pay(self)
+            && self.age >= 18 + self.years_in_higher_education
+            && self.studying_for.is_none();
+
+        let mut balance = self.balance;
+        let mut missed_payments = 0;
+        let mut defaults: Vec<DebtType> = Vec::new();
+
+        self.debts.retain_mut(|debt| {
+            if debt.debt_type == DebtType::Education && !paying_education {
+                return true;
+            }
+
+            debt.owed *= 1. + debt.interest_rate / 12.;
+            let payment = debt.get_payment(salary).min(debt.owed);
+
+            if balance >= payment {
+                balance -= payment;
+                debt.owed -= payment;
+                debt.missed_payments = 0;
+
+                return debt.owed > 0.;
+            }
+
+            debt.missed_payments += 1;
+            missed_payments += 1;
+            if debt.missed_payments < MISSED_PAYMENTS_BEFORE_DEFAULT {
+                return true;
+            }
+
+            defaults.push(debt.debt_type);
+            false
+        });
+        self.balance = balance;
+
+        if missed_payments > 0 || !defaults.is_empty() {
+            self.credit_score -= missed_payments * MISSED_PAYMENT_CREDIT_LOSS
+                + defaults.len() as i32 * DEFAULT_CREDIT_LOSS;
+        } else if !self.debts.is_empty() {
+            self.credit_score += ON_TIME_CREDIT_GAIN;
+        }
+        self.credit_score = self.credit_score.clamp(0, 100);
+        debt_state.defaults_last_month += defaults.len() as i32;
+
+        // The lender takes back the home
+        if defaults.contains(&DebtType::Mortgage) && self.housing == Housing::Owned {
+            self.housing = Housing::Homeless;
+            housing.occupied_homes -= 1;
+            housing.evictions_last_month += 1;
+        }
+
+        if self.age >= 18 && self.balance < 0. {
+            let shortfall = -self.balance;
+            if self.take_consumer_credit(shortfall) {
+                debt_state.new_credit_last_month += shortfall as i64;
+            }
+        }
+
+        self.get_monthly_debt_cost();
+    }
+
+    /// Borrows the amount, returns false if lenders turn the individual down
+    pub fn take_consumer_credit(&mut self, amount: f32) -> bool {
+        if self.credit_score < POOR_CREDIT_SCORE {
+            return false;
+        }
+
+        self.debts.push(Debt::new(
+            DebtType::ConsumerCredit,
+            amount,
+            CONSUMER_CREDIT_MONTHS,
+            self.credit_score,
+        ));
+        self.balance += amount;
+        self.get_monthly_debt_cost();
+
+        true
+    }
+
+    /// Bills the individual for medical care, anything they can not pay straight away is added
+    /// to their medical debt. Returns what they paid
+    pub fn bill_medical_care(&mut self, amount: f32) -> f32 {
+        let paid = amount.min(self.balance.max(0.));
+        self.balance -= paid;
+
+        if amount - paid <= 0. {
+            return paid;
+        }
+
+        let bill = Debt::new(
+            DebtType::Medical,
+            amount - paid,
+            MEDICAL_DEBT_MONTHS,
+            self.credit_score,
+        );
+        match self
+            .debts
+            .iter_mut()
+            .find(|debt| debt.debt_type == DebtType::Medical)
+        {
+            Some(debt) => {
+                debt.owed += bill.owed;
+                debt.minimum_monthly_payoff += bill.minimum_monthly_payoff;
+            }
+            None => self.debts.push(bill),
+        }
+        self.get_monthly_debt_cost();
+
+        paid
+    }
+
+    pub fn get_total_debt(&self) -> f32 {
+        self.debts.iter().map(|debt| debt.owed).sum()
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Debt {
     pub owed: f32,
-    pub minimum_monthly_payoff: f32, // Amount required to be paid per month
+    pub minimum_monthly_payoff: f32, // Percentage of monthly salary for education debt, the amount to pay each month for any other debt
     pub debt_type: DebtType,
+    pub interest_rate: f32, // Yearly, added to what is owed a month at a time
+    pub missed_payments: i32, // Missed in a row, the debt is defaulted on once there are too many
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DebtType {
     Education,
+    Mortgage,
+    ConsumerCredit,
+    Medical,
+}
+
+impl DebtType {
+    /// Yearly interest rate lenders charge someone with the given credit score
+    pub fn get_interest_rate(&self, credit_score: i32) -> f32 {
+        let risk_premium = (100 - credit_score.clamp(0, 100)) as f32 / 100. * CREDIT_RISK_PREMIUM;
+
+        match self {
+            DebtType::Education => 0.05,
+            DebtType::Mortgage => 0.04 + risk_premium / 4.,
+            DebtType::ConsumerCredit => 0.12 + risk_premium,
+            DebtType::Medical => 0.02,
+        }
+    }
 }
 
 impl Debt {
-    // TODO: add more types of debt
-    pub fn generate(person: &mut Person, salary: i32) -> Vec<Self> {
+    /// Borrows the amount, to be paid back in equal monthly payments over the given number of months
+    pub fn new(debt_type: DebtType, owed: f32, months: i32, credit_score: i32) -> Self {
+        let interest_rate = debt_type.get_interest_rate(credit_score);
+        let monthly_rate = interest_rate / 12.;
+
+        let payment = if monthly_rate > 0. {
+            owed * monthly_rate / (1. - (1. + monthly_rate).powi(-months))
+        } else {
+            owed / months as f32
+        };
+
+        Self {
+            owed,
+            minimum_monthly_payoff: payment,
+            debt_type,
+            interest_rate,
+            missed_payments: 0,
+        }
+    }
+
+    /// Education debt is paid back as a percentage of the individual's salary
+    pub fn education(owed: f32, salary_percentage: f32) -> Self {
+        Self {
+            owed,
+            minimum_monthly_payoff: salary_percentage,
+            debt_type: DebtType::Education,
+            interest_rate: DebtType::Education.get_interest_rate(STARTING_CREDIT_SCORE),
+            missed_payments: 0,
+        }
+    }
+
+    /// Amount due this month for an individual on the given salary
+    pub fn get_payment(&self, salary: i32) -> f32 {
+        match self.debt_type {
+            DebtType::Education => (salary / 12) as f32 * (self.minimum_monthly_payoff / 100.),
+            _ => self.minimum_monthly_payoff,
+        }
+    }
+
+    pub fn generate(person: &mut Person, salary: i32, rng: &mut GameRng) -> Vec<Self> {
         let mut debts: Vec<Self> = Vec::new();
-        let mut rng = rand::thread_rng();
 
         person.years_in_higher_education = rng.gen_range(1..4);
 
-        let mut owed: f32 = match person.education_level {
-            NoFormalEducation | HighSchoolDiploma => 0,
-            College | AssociateDegree => {
-                rng.gen_range(10000..12500) * person.years_in_higher_education
-            }
-            Bachelors => rng.gen_range(12000..15000) * person.years_in_higher_education,
-            AdvancedDegree => rng.gen_range(30000..34000) * person.years_in_higher_education,
-        } as f32;
+        let mut owed = Debt::get_tuition(
+            person.education_level,
+            person.years_in_higher_education,
+            rng,
+        );
 
         let education_finished_age = 18 + person.years_in_higher_education; // Age at which the individual finishes education
         let salary_percentage = rng.gen_range(11..35);
@@ -61,43 +252,144 @@ impl Debt {
         }
 
         owed -= match person.spending_behaviour {
-            SpendingBehaviour::One => Debt::get_education_prepaid_amount(3, 50..150),
-            SpendingBehaviour::Two => Debt::get_education_prepaid_amount(15, 100..300),
-            SpendingBehaviour::Three => Debt::get_education_prepaid_amount(30, 400..1200),
-            SpendingBehaviour::Four => Debt::get_education_prepaid_amount(72, 800..3500),
+            SpendingBehaviour::One => Debt::get_education_prepaid_amount(3, 50..150, rng),
+            SpendingBehaviour::Two => Debt::get_education_prepaid_amount(15, 100..300, rng),
+            SpendingBehaviour::Three => Debt::get_education_prepaid_amount(30, 400..1200, rng),
+            SpendingBehaviour::Four => Debt::get_education_prepaid_amount(72, 800..3500, rng),
         };
 
-        if owed < 0. {
-            return debts;
+        if owed > 0. {
+            debts.push(Debt::education(owed, salary_percentage as f32));
         }
 
-        debts.push(Self {
-            owed,
-            minimum_monthly_payoff: salary_percentage as f32,
-            debt_type: DebtType::Education,
-        });
+        // Those who save less of their money are more likely to be carrying a balance on their cards
+        let consumer_credit_chance = match person.spending_behaviour {
+            SpendingBehaviour::One => 40.,
+            SpendingBehaviour::Two => 25.,
+            SpendingBehaviour::Three => 10.,
+            SpendingBehaviour::Four => 3.,
+        };
+        if percentage_chance(consumer_credit_chance, rng) {
+            let owed = (salary.max(12_000) / 12) as f32 * float_range(0.5, 3., 1, rng);
+            debts.push(Debt::new(
+                DebtType::ConsumerCredit,
+                owed,
+                CONSUMER_CREDIT_MONTHS,
+                STARTING_CREDIT_SCORE,
+            ));
+        }
+
+        // Bills left over from past hospital stays
+        if percentage_chance(STARTING_MEDICAL_DEBT_CHANCE, rng) {
+            debts.push(Debt::new(
+                DebtType::Medical,
+                rng.gen_range(500..8000) as f32,
+                MEDICAL_DEBT_MONTHS,
+                STARTING_CREDIT_SCORE,
+            ));
+        }
 
         debts
     }
 
+    /// Total tuition for studying towards the education level for the given number of years
+    pub fn get_tuition(education_level: EducationLevel, years: i32, rng: &mut GameRng) -> f32 {
+        (match education_level {
+            NoFormalEducation | HighSchoolDiploma => 0,
+            College | AssociateDegree => rng.gen_range(10000..12500) * years,
+            Bachelors => rng.gen_range(12000..15000) * years,
+            AdvancedDegree => rng.gen_range(30000..34000) * years,
+        }) as f32
+    }
+
     /// Get amount that the student has already paid off, excluding what they are required to pay
     fn get_education_prepaid_amount(
         is_prepaid_chance_percentage: i32,
         prepaid_range: Range<i32>,
+        rng: &mut GameRng,
     ) -> f32 {
-        let is_prepaid = percentage_based_output_int::<bool>(hashmap! {
-            true => is_prepaid_chance_percentage,
-            false => 100 - is_prepaid_chance_percentage,
-        });
+        let is_prepaid = percentage_based_output_int::<bool>(
+            vec![
+                (true, is_prepaid_chance_percentage),
+                (false, 100 - is_prepaid_chance_percentage),
+            ],
+            rng,
+        );
 
         if is_prepaid {
             return 0.;
         }
 
-        rand::thread_rng().gen_range(prepaid_range) as f32
+        rng.gen_range(prepaid_range) as f32
     }
 
     pub fn required_to_pay(person: &Person) -> bool {
         person.age < 18 || person.salary as f32 >= US_DEBT_REPAYMENT_THRESHOLD
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patient(balance: f32) -> Person {
+        Person {
+            balance,
+            credit_score: STARTING_CREDIT_SCORE,
+            ..Person::default()
+        }
+    }
+
+    #[test]
+    fn medical_bills_are_paid_from_the_balance_first() {
+        let mut per = patient(500.);
+
+        assert_eq!(per.bill_medical_care(200.), 200.);
+        assert_eq!(per.balance, 300.);
+        assert!(per.debts.is_empty());
+
+        assert_eq!(per.bill_medical_care(0.), 0.);
+        assert!(per.debts.is_empty());
+    }
+
+    #[test]
+    fn unpaid_medical_bills_add_to_the_existing_medical_debt() {
+        let mut per = patient(100.);
+        per.debts.push(Debt::new(
+            DebtType::Mortgage,
+            50000.,
+            300,
+            STARTING_CREDIT_SCORE,
+        ));
+
+        assert_eq!(per.bill_medical_care(400.), 100.);
+        assert_eq!(per.balance, 0.);
+        let first_bill = Debt::new(
+            DebtType::Medical,
+            300.,
+            MEDICAL_DEBT_MONTHS,
+            STARTING_CREDIT_SCORE,
+        );
+
+        assert_eq!(per.bill_medical_care(200.), 0.);
+        let second_bill = Debt::new(
+            DebtType::Medical,
+            200.,
+            MEDICAL_DEBT_MONTHS,
+            STARTING_CREDIT_SCORE,
+        );
+
+        let medical_debts: Vec<&Debt> = per
+            .debts
+            .iter()
+            .filter(|debt| debt.debt_type == DebtType::Medical)
+            .collect();
+        assert_eq!(medical_debts.len(), 1);
+        assert_eq!(medical_debts[0].owed, 500.);
+        assert_eq!(
+            medical_debts[0].minimum_monthly_payoff,
+            first_bill.minimum_monthly_payoff + second_bill.minimum_monthly_payoff
+        );
+        assert_eq!(per.debts.len(), 2);
+    }
+}
@@ -0,0 +1,111 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::person::Person;
+use crate::{
+    common::util::{get_healthcare_group, percentage_chance, GameRng},
+    game::structs::{GameStateRules, HealthcareState},
+};
+
+pub const INFECTIOUS_DAYS: i32 = 10;
+pub const IMMUNITY_DAYS: i32 = 360; // Both recovering and being vaccinated protect for about a year
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Infection {
+    #[default]
+    Susceptible,
+    Infected {
+        days_left: i32, // Days until the individual recovers
+    },
+    Immune {
+        days_left: i32, // Days until the individual can catch the disease again
+    },
+}
+
+impl Person {
+    pub fn is_infected(&self) -> bool {
+        matches!(self.infection, Infection::Infected { .. })
+    }
+
+    /// Whether the individual can pass the disease on, patients in hospital are kept apart
+    pub fn is_infectious(&self) -> bool {
+        self.is_infected() && self.days_left_in_hospital.is_none()
+    }
+
+    /// Returns whether the individual caught the disease
+    pub fn infect(&mut self) -> bool {
+        if self.infection != Infection::Susceptible {
+            return false;
+        }
+
+        self.infection = Infection::Infected {
+            days_left: INFECTIOUS_DAYS,
+        };
+        true
+    }
+
+    /// Returns whether the individual needed the vaccine
+    pub fn vaccinate(&mut self) -> bool {
+        if self.infection != Infection::Susceptible {
+            return false;
+        }
+
+        self.infection = Infection::Immune {
+            days_left: IMMUNITY_DAYS,
+        };
+        true
+    }
+
+    /// How likely the individual is to catch the disease compared to an adult
+    pub fn get_susceptibility(&self) -> f32 {
+        match self.age {
+            a if a <= 18 => 0.6,
+            a if a <= 65 => 1.,
+            _ => 1.4,
+        }
+    }
+
+    /// Runs daily, the infected may lose health and end up in hospital, which is more dangerous
+    /// for the elderly and when their part of the health service is already stretched
+    pub fn progress_infection(
+        &mut self,
+        healthcare: &mut HealthcareState,
+        rules: &GameStateRules,
+        rng: &mut GameRng,
+    ) {
+        match &mut self.infection {
+            Infection::Susceptible => (),
+            Infection::Immune { days_left } => {
+                *days_left -= 1;
+                if *days_left <= 0 {
+                    self.infection = Infection::Susceptible;
+                }
+            }
+            Infection::Infected { days_left } => {
+                *days_left -= 1;
+                if *days_left <= 0 {
+                    self.infection = Infection::Immune {
+                        days_left: IMMUNITY_DAYS,
+                    };
+                }
+
+                let severity_chance = match self.age {
+                    a if a <= 18 => 2.,
+                    a if a <= 65 => 4.,
+                    _ => 12.,
+                };
+
+                let healthcare_group = get_healthcare_group(self.age, healthcare);
+                let occupancy = match healthcare_group.total_capacity {
+                    0 => 1.,
+                    total => 1. - healthcare_group.current_capacity as f32 / total as f32,
+                };
+
+                if percentage_chance(severity_chance * (1. + occupancy), rng) {
+                    let health_loss = rng.gen_range(2..=6);
+                    self.remove_health(health_loss, healthcare, rules, rng);
+                }
+            }
+        }
+    }
+}
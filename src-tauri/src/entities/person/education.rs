@@ -0,0 +1,280 @@
+use rand::Rng;
+
+use super::{
+    debt::Debt,
+    person::{EducationLevel, EducationLevel::*, Person},
+};
+use crate::{
+    common::{config::Config, util::GameRng},
+    game::{
+        generation::get_expected_salary_range,
+        structs::{EducationState, TuitionRule},
+    },
+};
+
+pub const EXPECTED_SPENDING_PER_PUPIL: i64 = 1000; // Monthly education budget per pupil for schools to run as usual
+pub const SCHOOL_STARTING_AGE: i32 = 5;
+pub const MINIMUM_EDUCATION_FUNDING: f32 = 0.5; // Even unfunded schools get some children through
+pub const MAXIMUM_EDUCATION_FUNDING: f32 = 1.5;
+const TUITION_ENROLMENT_BOOST: f32 = 0.5; // How much more likely people are to study when all of their tuition is covered
+
+const HIGHER_EDUCATION: [EducationLevel; 4] = [College, AssociateDegree, Bachelors, AdvancedDegree];
+
+fn get_attainment_chance(config: &Config, education_level: EducationLevel) -> i32 {
+    match education_level {
+        NoFormalEducation => config.no_education.chance,
+        HighSchoolDiploma => config.high_school_diploma.chance,
+        College => config.college.chance,
+        AssociateDegree => config.associate_degree.chance,
+        Bachelors => config.bachelors.chance,
+        AdvancedDegree => config.advanced_degree.chance,
+    }
+}
+
+/// Chance of reaching at least the given level, out of 100
+fn get_chance_of_at_least(config: &Config, education_level: EducationLevel) -> i32 {
+    [NoFormalEducation, HighSchoolDiploma]
+        .into_iter()
+        .chain(HIGHER_EDUCATION)
+        .filter(|level| (*level as u8) >= (education_level as u8))
+        .map(|level| get_attainment_chance(config, level))
+        .sum()
+}
+
+fn get_years_of_study(education_level: EducationLevel) -> i32 {
+    match education_level {
+        College | AssociateDegree => 2,
+        Bachelors => 3,
+        AdvancedDegree => 5,
+        NoFormalEducation | HighSchoolDiploma => 0,
+    }
+}
+
+impl Person {
+    /// Runs on the individual's birthday. At 18 they may finish school and go on to higher
+    /// education, which they finish once they have studied for long enough.
+    /// The configured chances of each education level are followed when schools are funded as
+    /// expected, a larger budget or covered tuition makes people more likely to carry on studying
+    pub fn progress_education(
+        &mut self,
+        config: &Config,
+        education: &mut EducationState,
+        tuition_rule: &TuitionRule,
+        rng: &mut GameRng,
+    ) {
+        if let Some(studying_for) = self.studying_for {
+            if self.age >= 18 + self.years_in_higher_education {
+                self.studying_for = None;
+                self.set_education_level(config, studying_for);
+                education.graduates_last_month += 1;
+            }
+
+            return;
+        }
+
+        if self.age != 18 || self.education_level != NoFormalEducation {
+            return;
+        }
+
+        let graduation_chance = get_chance_of_at_least(config, HighSchoolDiploma) as f32 / 100.;
+        if !rng.gen_bool((graduation_chance * education.funding).clamp(0., 1.) as f64) {
+            return;
+        }
+        self.set_education_level(config, HighSchoolDiploma);
+
+        let tuition_coverage = if tuition_rule.enabled {
+            tuition_rule.coverage
+        } else {
+            0.
+        };
+        let enrolment_chance = get_chance_of_at_least(config, College) as f32
+            / get_chance_of_at_least(config, HighSchoolDiploma).max(1) as f32
+            * education.funding
+            * (1. + tuition_coverage * TUITION_ENROLMENT_BOOST);
+        if !rng.gen_bool(enrolment_chance.clamp(0., 1.) as f64) {
+            return;
+        }
+
+        let studying_for = self.choose_higher_education(config, rng);
+        self.studying_for = Some(studying_for);
+        self.years_in_higher_education = get_years_of_study(studying_for);
+
+        let tuition = Debt::get_tuition(studying_for, self.years_in_higher_education, rng);
+        let covered = tuition * tuition_coverage;
+        education.tuition_owed += covered as i64;
+
+        if tuition - covered > 0. {
+            self.debts.push(Debt::education(
+                tuition - covered,
+                rng.gen_range(11..35) as f32,
+            ));
+            self.get_monthly_debt_cost();
+        }
+    }
+
+    /// Picks what to study, weighted by how common each level of higher education is
+    fn choose_higher_education(&self, config: &Config, rng: &mut GameRng) -> EducationLevel {
+        let total: i32 = HIGHER_EDUCATION
+            .iter()
+            .map(|level| get_attainment_chance(config, *level))
+            .sum();
+        let mut roll = rng.gen_range(0..total.max(1));
+
+        for level in HIGHER_EDUCATION {
+            roll -= get_attainment_chance(config, level);
+            if roll < 0 {
+                return level;
+            }
+        }
+
+        College
+    }
+
+    fn set_education_level(&mut self, config: &Config, education_level: EducationLevel) {
+        self.education_level = education_level;
+        self.expected_salary_range = get_expected_salary_range(config, &education_level);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::config::{load_config, ConfigRange, EducationConfig},
+        entities::person::debt::DebtType,
+    };
+    use rand::SeedableRng;
+
+    fn education(chance: i32) -> EducationConfig {
+        EducationConfig {
+            chance,
+            salary_range: ConfigRange {
+                min: 20000,
+                max: 30000,
+            },
+        }
+    }
+
+    /// Everyone finishes school and goes on to a bachelors degree
+    fn config_with_everyone_studying() -> Config {
+        let mut config = load_config().unwrap();
+        config.no_education = education(0);
+        config.high_school_diploma = education(0);
+        config.college = education(0);
+        config.associate_degree = education(0);
+        config.bachelors = education(100);
+        config.advanced_degree = education(0);
+        config
+    }
+
+    fn school_leaver() -> Person {
+        Person {
+            age: 18,
+            ..Person::default()
+        }
+    }
+
+    fn education_debt(per: &Person) -> f32 {
+        per.debts
+            .iter()
+            .filter(|debt| debt.debt_type == DebtType::Education)
+            .map(|debt| debt.owed)
+            .sum()
+    }
+
+    #[test]
+    fn students_graduate_once_they_have_studied_long_enough() {
+        let config = config_with_everyone_studying();
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut education = EducationState::default();
+        let mut per = school_leaver();
+
+        per.progress_education(&config, &mut education, &TuitionRule::default(), &mut rng);
+        assert!(per.studying_for == Some(Bachelors));
+        assert!(per.education_level == HighSchoolDiploma);
+        assert_eq!(per.years_in_higher_education, 3);
+
+        for age in 19..21 {
+            per.age = age;
+            per.progress_education(&config, &mut education, &TuitionRule::default(), &mut rng);
+            assert!(per.studying_for.is_some());
+        }
+        assert_eq!(education.graduates_last_month, 0);
+
+        per.age = 21;
+        per.progress_education(&config, &mut education, &TuitionRule::default(), &mut rng);
+        assert!(per.studying_for.is_none());
+        assert!(per.education_level == Bachelors);
+        assert_eq!(education.graduates_last_month, 1);
+    }
+
+    #[test]
+    fn better_funded_schools_get_more_people_through() {
+        let mut config = load_config().unwrap();
+        config.no_education = education(50);
+        config.high_school_diploma = education(50);
+        config.college = education(0);
+        config.associate_degree = education(0);
+        config.bachelors = education(0);
+        config.advanced_degree = education(0);
+
+        let graduates = |funding| {
+            let mut rng = GameRng::seed_from_u64(0);
+            let mut education = EducationState {
+                funding,
+                ..EducationState::default()
+            };
+
+            (0..1000)
+                .filter(|_| {
+                    let mut per = school_leaver();
+                    per.progress_education(
+                        &config,
+                        &mut education,
+                        &TuitionRule::default(),
+                        &mut rng,
+                    );
+                    per.education_level == HighSchoolDiploma
+                })
+                .count()
+        };
+
+        let underfunded = graduates(MINIMUM_EDUCATION_FUNDING);
+        let funded = graduates(1.);
+        let well_funded = graduates(MAXIMUM_EDUCATION_FUNDING);
+        assert!((200..300).contains(&underfunded));
+        assert!((450..550).contains(&funded));
+        assert!((700..800).contains(&well_funded));
+        assert_eq!(graduates(0.), 0);
+    }
+
+    #[test]
+    fn covered_tuition_is_owed_by_the_government_and_the_rest_by_the_student() {
+        let config = config_with_everyone_studying();
+        let mut rng = GameRng::seed_from_u64(0);
+
+        let start_studying = |coverage, rng: &mut GameRng| {
+            let mut education = EducationState::default();
+            let tuition_rule = TuitionRule {
+                enabled: true,
+                coverage,
+            };
+            let mut per = school_leaver();
+            per.progress_education(&config, &mut education, &tuition_rule, rng);
+
+            (education.tuition_owed as f32, education_debt(&per))
+        };
+
+        let (government, student) = start_studying(0., &mut rng);
+        assert_eq!(government, 0.);
+        assert!(student >= 36000.);
+
+        let (government, student) = start_studying(0.5, &mut rng);
+        assert!(government >= 18000.);
+        assert!((government - student).abs() <= 1.);
+
+        let (government, student) = start_studying(1., &mut rng);
+        assert!(government >= 36000.);
+        assert_eq!(student, 0.);
+    }
+}
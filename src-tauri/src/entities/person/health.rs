@@ -1,9 +1,11 @@
-use super::{debt::Debt, person::Person};
+use super::person::Person;
 use crate::{
-    common::util::{get_healthcare_group, percentage_based_output_int},
-    game::structs::{GameStateRules, HealthcareState},
+    common::{
+        config::Config,
+        util::{get_healthcare_group, percentage_based_output_int, GameRng},
+    },
+    game::structs::{EducationState, GameStateRules, HealthcareState, HospitalChargesRule},
 };
-use maplit::hashmap;
 use rand::Rng;
 
 impl Person {
@@ -55,11 +57,31 @@ impl Person {
         true
     }
 
+    /// Bills the patient for a day in hospital at what their bed costs to run, when hospital
+    /// charges are in place. The government pays its share of the bill
+    pub fn bill_hospital_day(
+        &mut self,
+        rule: &HospitalChargesRule,
+        healthcare: &mut HealthcareState,
+    ) {
+        let daily_cost = healthcare.cost_per_hospital_capacity / 30.;
+        if !rule.enabled || !daily_cost.is_finite() {
+            return;
+        }
+
+        let charge = daily_cost * (1. - rule.coverage.clamp(0., 1.));
+        let paid = self.bill_medical_care(charge);
+
+        healthcare.charges_paid_last_month += paid as i64;
+        healthcare.charges_unpaid_last_month += (charge - paid) as i64;
+    }
+
     pub fn remove_health(
         &mut self,
         amount: i32,
         healthcare: &mut HealthcareState,
         rules: &GameStateRules,
+        rng: &mut GameRng,
     ) {
         self.health_percentage -= amount;
         if self.health_percentage <= 2 {
@@ -101,12 +123,12 @@ impl Person {
 
         if healthcare_group.current_capacity == 0 || !self.eligible_for_healthcare(rules) {
             healthcare.month_unhospitalised_count += 1;
-            self.die_based_on_chance(death_chance * 3, 0); // will die for the proceeding day - TODO: die on the current day instead, if possible
+            self.die_based_on_chance(death_chance * 3, 0, rng); // will die for the proceeding day - TODO: die on the current day instead, if possible
             return;
         }
 
         healthcare_group.current_capacity -= 1;
-        self.hospitalize(percentage_below_hospitalisation, death_chance, amount);
+        self.hospitalize(percentage_below_hospitalisation, death_chance, amount, rng);
     }
 
     pub fn hospitalize(
@@ -114,9 +136,9 @@ impl Person {
         percentage_below_hospitalisation: i32,
         death_chance: i32,
         initial_health_loss: i32,
+        rng: &mut GameRng,
     ) {
         self.hospitalisation_count += 1;
-        let mut rng = rand::thread_rng();
 
         let hospital_days = death_chance;
         self.days_left_in_hospital = Some(hospital_days);
@@ -133,7 +155,7 @@ impl Person {
 
         self.hospitalisation_percentage += hospitalisation_percent_increase;
 
-        self.die_based_on_chance(death_chance, hospital_days); // i swear my code gets worse every time i do this
+        self.die_based_on_chance(death_chance, hospital_days, rng); // i swear my code gets worse every time i do this
 
         self.health_percentage = self.hospitalisation_percentage + (initial_health_loss / 2);
         if self.health_percentage > self.maximum_health {
@@ -141,11 +163,8 @@ impl Person {
         }
     }
 
-    fn die_based_on_chance(&mut self, chance: i32, days_until_death: i32) {
-        let die = percentage_based_output_int(hashmap! {
-            true => chance,
-            false => 100 - chance,
-        });
+    fn die_based_on_chance(&mut self, chance: i32, days_until_death: i32, rng: &mut GameRng) {
+        let die = percentage_based_output_int(vec![(true, chance), (false, 100 - chance)], rng);
 
         if die {
             self.die(days_until_death); // massive L, the person will automatically die the following day
@@ -153,11 +172,11 @@ impl Person {
     }
 
     pub fn die(&mut self, days_until_death: i32) {
+        // Their estate is settled by the game state once they are gone
         self.days_until_death = Some(days_until_death);
-        // TODO: inheritance of money
     }
 
-    pub fn generate_health(&mut self) {
+    pub fn generate_health(&mut self, rng: &mut GameRng) {
         let (
             health_range,
             hospitalisation_percentage_range,
@@ -171,23 +190,25 @@ impl Person {
             _ => (20..30, 45..70, 5..25, 35..50),
         };
 
-        let mut rng = rand::thread_rng();
-
         self.health_percentage = rng.gen_range(health_range);
         self.hospitalisation_percentage = rng.gen_range(hospitalisation_percentage_range);
         self.hospitalisation_count = rng.gen_range(hospitalisation_count_range);
         self.maximum_health = rng.gen_range(maximum_health_range);
     }
 
-    pub fn grow_up(&mut self) {
+    pub fn grow_up(
+        &mut self,
+        config: &Config,
+        education: &mut EducationState,
+        rules: &GameStateRules,
+        rng: &mut GameRng,
+    ) {
         self.age += 1;
 
-        let education_finished_age = 18 + self.years_in_higher_education;
-        if self.age == education_finished_age {}
+        self.progress_education(config, education, &rules.tuition_rule, rng);
 
         if self.age == 18 {
-            self.debts = Debt::generate(self, self.salary);
-            self.generate_daily_food_spending();
+            self.generate_daily_food_spending(rng);
         }
 
         if self.age % 2 == 0 {
@@ -196,7 +217,7 @@ impl Person {
     }
 
     // This should be executed everyday
-    pub fn replenish_health(&mut self) {
+    pub fn replenish_health(&mut self, rng: &mut GameRng) {
         // Age determines the chance that the individual will regenerate their health
         let replenish_chance = 30
             * match self.age {
@@ -205,10 +226,87 @@ impl Person {
                 _ => 1,
             };
 
-        let replenish = rand::thread_rng().gen_range(0..=replenish_chance) == replenish_chance;
+        let replenish = rng.gen_range(0..=replenish_chance) == replenish_chance;
 
         if replenish {
             self.add_health(1);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::person::debt::DebtType;
+
+    fn patient(balance: f32) -> Person {
+        Person {
+            balance,
+            ..Person::default()
+        }
+    }
+
+    fn medical_debt(per: &Person) -> f32 {
+        per.debts
+            .iter()
+            .filter(|debt| debt.debt_type == DebtType::Medical)
+            .map(|debt| debt.owed)
+            .sum()
+    }
+
+    fn bill(per: &mut Person, coverage: f32) -> HealthcareState {
+        let mut healthcare = HealthcareState {
+            cost_per_hospital_capacity: 3000.,
+            ..HealthcareState::default()
+        };
+        let rule = HospitalChargesRule {
+            enabled: true,
+            coverage,
+        };
+        per.bill_hospital_day(&rule, &mut healthcare);
+
+        healthcare
+    }
+
+    #[test]
+    fn patients_pay_what_the_government_does_not_cover() {
+        let mut per = patient(1000.);
+        let healthcare = bill(&mut per, 0.);
+        assert_eq!(per.balance, 900.);
+        assert_eq!(healthcare.charges_paid_last_month, 100);
+
+        let healthcare = bill(&mut per, 0.5);
+        assert_eq!(per.balance, 850.);
+        assert_eq!(healthcare.charges_paid_last_month, 50);
+
+        let healthcare = bill(&mut per, 1.);
+        assert_eq!(per.balance, 850.);
+        assert_eq!(healthcare.charges_paid_last_month, 0);
+        assert_eq!(healthcare.charges_unpaid_last_month, 0);
+        assert!(per.debts.is_empty());
+    }
+
+    #[test]
+    fn patients_owe_what_they_can_not_pay() {
+        let mut per = patient(30.);
+        let healthcare = bill(&mut per, 0.);
+
+        assert_eq!(per.balance, 0.);
+        assert_eq!(healthcare.charges_paid_last_month, 30);
+        assert_eq!(healthcare.charges_unpaid_last_month, 70);
+        assert_eq!(medical_debt(&per), 70.);
+    }
+
+    #[test]
+    fn patients_are_not_billed_without_hospital_charges() {
+        let mut per = patient(1000.);
+        let mut healthcare = HealthcareState {
+            cost_per_hospital_capacity: 3000.,
+            ..HealthcareState::default()
+        };
+        per.bill_hospital_day(&HospitalChargesRule::default(), &mut healthcare);
+
+        assert_eq!(per.balance, 1000.);
+        assert_eq!(healthcare.charges_paid_last_month, 0);
+    }
+}
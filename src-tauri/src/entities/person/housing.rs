@@ -0,0 +1,341 @@
+use crate::{
+    common::util::{float_range, GameRng},
+    entities::business::{Business, ProductType},
+    game::structs::{HousingState, HousingSubsidyRule, RentCapRule, SalesTaxRule},
+};
+
+use super::{
+    debt::{Debt, DebtType, MORTGAGE_CREDIT_SCORE, POOR_CREDIT_SCORE},
+    person::Person,
+};
+use serde::{Deserialize, Serialize};
+
+const MISSED_PAYMENTS_BEFORE_EVICTION: i32 = 2;
+const MAXIMUM_INCOME_PERCENTAGE: f32 = 0.4; // Nobody takes on a home costing more than this much of their monthly income
+const HOUSE_PRICE_RENT_MULTIPLIER: f32 = 180.; // A house costs about 15 years of rent
+const MORTGAGE_DEPOSIT: f32 = 0.1; // Part of the house price paid up front
+const MORTGAGE_MONTHS: i32 = 300; // 25 years
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Housing {
+    #[default]
+    Dependent, // Lives with someone else, such as children with their parents
+    Renting {
+        rent: i32,
+        missed_payments: i32,
+    },
+    Owned, // Any mortgage on the home is one of the individual's debts
+    Homeless,
+}
+
+impl Person {
+    pub fn is_homeless(&self) -> bool {
+        self.housing == Housing::Homeless
+    }
+
+    /// Whether the individual takes up one of the homes in the housing stock
+    pub fn has_home(&self) -> bool {
+        matches!(self.housing, Housing::Renting { .. } | Housing::Owned)
+    }
+
+    /// Rent agreed when the individual moved in, before any rent cap
+    pub fn get_monthly_rent(&self) -> i32 {
+        match self.housing {
+            Housing::Renting { rent, .. } => rent,
+            _ => 0,
+        }
+    }
+
+    pub fn get_rent(rule: &RentCapRule, rent: i32) -> i32 {
+        if rule.enabled {
+            return rent.min(rule.maximum_rent);
+        }
+
+        rent
+    }
+
+    /// Adults without a home move into a vacant one they can afford, buying it if they have enough for the deposit.
+    /// Lenders and landlords will not take on anyone with a poor credit score.
+    /// Returns what was paid up front, which goes to the housing businesses. Rent has sales tax on top
+    pub fn find_housing(
+        &mut self,
+        housing: &mut HousingState,
+        rent_cap_rule: &RentCapRule,
+        sales_tax_rule: &SalesTaxRule,
+        sales_tax: &mut f64,
+        rng: &mut GameRng,
+    ) -> f32 {
+        if self.age < 18
+            || self.has_home()
+            || housing.vacant_homes() <= 0
+            || self.credit_score < POOR_CREDIT_SCORE
+        {
+            return 0.;
+        }
+
+        let market_rent = housing.market_rent as f32 * float_range(0.7, 1.3, 2, rng);
+        let rent = Self::get_rent(rent_cap_rule, market_rent as i32);
+        let affordable_payment = (self.salary as f32 / 12.) * MAXIMUM_INCOME_PERCENTAGE;
+
+        // Rent caps do not affect house prices
+        let house_price = market_rent * HOUSE_PRICE_RENT_MULTIPLIER;
+        let deposit = house_price * MORTGAGE_DEPOSIT;
+        let mortgage = Debt::new(
+            DebtType::Mortgage,
+            house_price - deposit,
+            MORTGAGE_MONTHS,
+            self.credit_score,
+        );
+
+        // Only buy if there are savings left after the deposit
+        if self.balance >= deposit * 2.
+            && self.credit_score >= MORTGAGE_CREDIT_SCORE
+            && mortgage.minimum_monthly_payoff <= affordable_payment
+        {
+            self.balance -= deposit;
+            self.debts.push(mortgage);
+            self.get_monthly_debt_cost();
+            self.housing = Housing::Owned;
+            housing.occupied_homes += 1;
+
+            return deposit;
+        }
+
+        // The first month of rent is paid up front, which the homeless often can not afford
+        let tax = rent as f32 * Business::get_sales_tax_rate(sales_tax_rule, ProductType::Housing);
+        if rent as f32 > affordable_payment || self.balance < rent as f32 + tax {
+            return 0.;
+        }
+
+        self.balance -= rent as f32 + tax;
+        *sales_tax += tax as f64;
+        self.housing = Housing::Renting {
+            rent,
+            missed_payments: 0,
+        };
+        housing.occupied_homes += 1;
+
+        rent as f32
+    }
+
+    /// Returns the subsidy the government pays towards the individual's rent this month
+    pub fn get_housing_subsidy(&self, rule: &HousingSubsidyRule, subsidised_count: i32) -> i32 {
+        let rent = match self.housing {
+            Housing::Renting { rent, .. } => rent,
+            _ => return 0,
+        };
+
+        if !rule.enabled
+            || self.salary >= rule.maximum_salary
+            || subsidised_count >= rule.people_count
+        {
+            return 0;
+        }
+
+        rule.subsidy.min(rent)
+    }
+
+    /// Pays this month's rent with sales tax on top, evicting the individual if they keep missing payments.
+    /// Mortgages are paid along with the individual's other debts. Returns the rent paid to landlords
+    pub fn pay_housing(
+        &mut self,
+        housing: &mut HousingState,
+        rent_cap_rule: &RentCapRule,
+        sales_tax_rule: &SalesTaxRule,
+        sales_tax: &mut f64,
+    ) -> f32 {
+        let (rent, missed_payments) = match &mut self.housing {
+            Housing::Renting {
+                rent,
+                missed_payments,
+            } => (Self::get_rent(rent_cap_rule, *rent) as f32, missed_payments),
+            _ => return 0.,
+        };
+        let tax = rent * Business::get_sales_tax_rate(sales_tax_rule, ProductType::Housing);
+
+        if self.balance >= rent + tax {
+            self.balance -= rent + tax;
+            *sales_tax += tax as f64;
+            *missed_payments = 0;
+
+            return rent;
+        }
+
+        *missed_payments += 1;
+        if *missed_payments >= MISSED_PAYMENTS_BEFORE_EVICTION {
+            self.housing = Housing::Homeless;
+            housing.occupied_homes -= 1;
+            housing.evictions_last_month += 1;
+        }
+
+        0.
+    }
+
+    /// Frees the individual's home, for when they die or move in with their partner.
+    /// An owned home is sold, which pays off its mortgage
+    pub fn leave_home(&mut self, housing: &mut HousingState) {
+        if self.has_home() {
+            housing.occupied_homes -= 1;
+        }
+
+        if self.housing == Housing::Owned {
+            self.debts
+                .retain(|debt| debt.debt_type != DebtType::Mortgage);
+            self.get_monthly_debt_cost();
+        }
+
+        self.housing = Housing::Dependent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::person::debt::STARTING_CREDIT_SCORE;
+    use rand::SeedableRng;
+
+    fn renter(balance: f32, rent: i32) -> (Person, HousingState) {
+        let per = Person {
+            age: 30,
+            balance,
+            housing: Housing::Renting {
+                rent,
+                missed_payments: 0,
+            },
+            ..Person::default()
+        };
+        let housing = HousingState {
+            total_homes: 10,
+            occupied_homes: 1,
+            ..HousingState::default()
+        };
+
+        (per, housing)
+    }
+
+    #[test]
+    fn renters_are_evicted_after_missing_two_payments() {
+        let (mut per, mut housing) = renter(1000., 800);
+        let rule = RentCapRule::default();
+
+        assert_eq!(
+            per.pay_housing(&mut housing, &rule, &SalesTaxRule::default(), &mut 0.),
+            800.
+        );
+        assert_eq!(per.balance, 200.);
+
+        assert_eq!(
+            per.pay_housing(&mut housing, &rule, &SalesTaxRule::default(), &mut 0.),
+            0.
+        );
+        assert!(per.has_home());
+
+        assert_eq!(
+            per.pay_housing(&mut housing, &rule, &SalesTaxRule::default(), &mut 0.),
+            0.
+        );
+        assert!(per.is_homeless());
+        assert_eq!(housing.occupied_homes, 0);
+        assert_eq!(housing.evictions_last_month, 1);
+    }
+
+    #[test]
+    fn moving_in_is_paid_for_up_front() {
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut housing = HousingState {
+            total_homes: 10,
+            market_rent: 500,
+            ..HousingState::default()
+        };
+        let mut per = Person {
+            age: 30,
+            salary: 60000,
+            balance: 1000.,
+            credit_score: STARTING_CREDIT_SCORE,
+            ..Person::default()
+        };
+
+        let paid = per.find_housing(
+            &mut housing,
+            &RentCapRule::default(),
+            &SalesTaxRule::default(),
+            &mut 0.,
+            &mut rng,
+        );
+        assert!(paid > 0.);
+        assert_eq!(per.balance, 1000. - paid);
+        assert_eq!(paid, per.get_monthly_rent() as f32);
+        assert_eq!(housing.occupied_homes, 1);
+
+        // Someone with a home already pays nothing
+        assert_eq!(
+            per.find_housing(
+                &mut housing,
+                &RentCapRule::default(),
+                &SalesTaxRule::default(),
+                &mut 0.,
+                &mut rng,
+            ),
+            0.
+        );
+    }
+
+    #[test]
+    fn rent_cap_limits_what_renters_pay() {
+        let (mut per, mut housing) = renter(1000., 800);
+        let rule = RentCapRule {
+            enabled: true,
+            maximum_rent: 500,
+        };
+
+        assert_eq!(
+            per.pay_housing(&mut housing, &rule, &SalesTaxRule::default(), &mut 0.),
+            500.
+        );
+        assert_eq!(per.balance, 500.);
+    }
+
+    #[test]
+    fn sales_tax_is_charged_on_rent() {
+        let (mut per, mut housing) = renter(1000., 800);
+        let sales_tax_rule = SalesTaxRule {
+            enabled: true,
+            housing: 0.1,
+            ..SalesTaxRule::default()
+        };
+        let mut sales_tax = 0.;
+
+        let paid = per.pay_housing(
+            &mut housing,
+            &RentCapRule::default(),
+            &sales_tax_rule,
+            &mut sales_tax,
+        );
+        assert_eq!(paid, 800.);
+        assert_eq!(sales_tax, 80.);
+        assert_eq!(per.balance, 120.);
+    }
+
+    #[test]
+    fn subsidy_goes_to_low_earning_renters() {
+        let (mut per, _) = renter(0., 800);
+        per.salary = 20000;
+        let rule = HousingSubsidyRule {
+            enabled: true,
+            people_count: 1,
+            subsidy: 300,
+            maximum_salary: 30000,
+            budget_cost: 0,
+        };
+
+        assert_eq!(per.get_housing_subsidy(&rule, 0), 300);
+        assert_eq!(per.get_housing_subsidy(&rule, 1), 0);
+
+        per.salary = 40000;
+        assert_eq!(per.get_housing_subsidy(&rule, 0), 0);
+
+        per.salary = 20000;
+        per.housing = Housing::Owned;
+        assert_eq!(per.get_housing_subsidy(&rule, 0), 0);
+    }
+}
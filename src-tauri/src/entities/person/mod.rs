@@ -1,4 +1,9 @@
 pub mod debt;
+pub mod disease;
+pub mod education;
 pub mod health;
+pub mod housing;
+pub mod pension;
+#[allow(clippy::module_inception)]
 pub mod person;
 pub mod welfare;
@@ -0,0 +1,117 @@
+use super::person::{Job, Person};
+use crate::{common::config::Config, game::structs::PensionRule};
+
+impl Person {
+    pub fn get_retirement_age(config: &Config, rule: &PensionRule) -> i32 {
+        if rule.enabled {
+            return rule.retirement_age;
+        }
+
+        config.pension.retirement_age
+    }
+
+    /// Keeps a running average of the individual's salary while they work, which their pension
+    /// is based on. This is run on a monthly basis
+    pub fn record_career_salary(&mut self) {
+        let total = self.career_salary as i64 * self.career_months as i64 + self.salary as i64;
+
+        self.career_months += 1;
+        self.career_salary = (total / self.career_months as i64) as i32;
+    }
+
+    /// Yearly pension the individual retires on
+    pub fn get_pension(&self, config: &Config) -> i32 {
+        let pension = self.career_salary as f32 * config.pension.replacement_rate;
+
+        (pension as i32).max(config.pension.minimum_pension)
+    }
+
+    pub fn retire(&mut self, config: &Config) {
+        self.job = Job::Retired;
+        self.set_salary(self.get_pension(config));
+    }
+
+    /// Pension owed to the individual this month, retirees with more savings than the rule allows
+    /// are not paid
+    pub fn get_pension_payment(&self, rule: &PensionRule) -> i32 {
+        if self.job != Job::Retired {
+            return 0;
+        }
+
+        if rule.enabled && rule.maximum_balance > 0 && self.balance as i64 > rule.maximum_balance {
+            return 0;
+        }
+
+        self.salary / 12
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::load_config;
+
+    fn retiree(salary: i32, balance: f32) -> Person {
+        Person {
+            job: Job::Retired,
+            salary,
+            balance,
+            ..Person::default()
+        }
+    }
+
+    #[test]
+    fn career_salary_is_the_average_of_every_month_worked() {
+        let mut per = Person {
+            salary: 30000,
+            ..Person::default()
+        };
+
+        per.record_career_salary();
+        assert_eq!(per.career_salary, 30000);
+
+        per.salary = 60000;
+        per.record_career_salary();
+        per.record_career_salary();
+        assert_eq!(per.career_salary, 50000);
+        assert_eq!(per.career_months, 3);
+
+        let mut config = load_config().unwrap();
+        config.pension.replacement_rate = 0.5;
+        config.pension.minimum_pension = 10000;
+        per.retire(&config);
+        assert!(per.job == Job::Retired);
+        assert_eq!(per.salary, 25000);
+    }
+
+    #[test]
+    fn wealthy_retirees_are_not_paid_when_pensions_are_means_tested() {
+        let rule = PensionRule {
+            enabled: true,
+            retirement_age: 65,
+            maximum_balance: 50000,
+        };
+
+        assert_eq!(retiree(24000, 50000.).get_pension_payment(&rule), 2000);
+        assert_eq!(retiree(24000, 50001.).get_pension_payment(&rule), 0);
+
+        let no_means_test = PensionRule {
+            maximum_balance: 0,
+            ..rule
+        };
+        assert_eq!(
+            retiree(24000, 500000.).get_pension_payment(&no_means_test),
+            2000
+        );
+        assert_eq!(
+            retiree(24000, 500000.).get_pension_payment(&PensionRule::default()),
+            2000
+        );
+
+        let worker = Person {
+            salary: 24000,
+            ..Person::default()
+        };
+        assert_eq!(worker.get_pension_payment(&rule), 0);
+    }
+}
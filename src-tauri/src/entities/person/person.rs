@@ -4,32 +4,39 @@ use crate::{
     common::{
         errors::{Error, IncResult},
         util::{
-            chance_one_in, float_range, generate_unemployed_salary, percentage_based_output_int,
-            percentage_chance, Date,
+            chance_one_in, float_range, generate_id, generate_unemployed_salary,
+            percentage_based_output_int, percentage_chance, Date, GameRng,
         },
     },
     entities::business::{Business, ProductType},
     game::{
         generation::{generate_education_level, get_expected_salary_range},
-        structs::{GameStateRules, HealthcareState, TaxRule},
+        structs::{
+            EducationState, GameStateRules, HealthcareState, IncomeTaxState, SalesTaxRule, TaxRule,
+        },
     },
     percentage_of,
 };
-use maplit::hashmap;
 use rand::Rng;
-use std::{collections::HashMap, ops::Range};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+};
 use uuid::Uuid;
 use EducationLevel::*;
 
 use super::{
-    debt::Debt,
+    debt::{Debt, POOR_CREDIT_SCORE, STARTING_CREDIT_SCORE},
+    disease::Infection,
+    housing::Housing,
     welfare::{
         WelfareMachine, WELFARE_IMPACT_FIVE, WELFARE_IMPACT_FOUR, WELFARE_IMPACT_SEVEN,
         WELFARE_IMPACT_THREE, WELFARE_IMPACT_TWO,
     },
 };
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Birthday {
     pub day: i32,   // 1-30
     pub month: i32, // 1-12
@@ -37,9 +44,7 @@ pub struct Birthday {
 
 impl Birthday {
     /// Generates a random birthday date
-    pub fn generate() -> Self {
-        let mut rng = rand::thread_rng();
-
+    pub fn generate(rng: &mut GameRng) -> Self {
         Self {
             day: rng.gen_range(1..=30),
             month: rng.gen_range(1..=12),
@@ -54,23 +59,27 @@ impl Birthday {
     }
 }
 
-#[derive(Default, Clone, Eq, PartialEq)]
+#[derive(Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Gender {
     #[default]
     Male,
     Female,
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Person {
     pub id: Uuid,
 
     pub education_level: EducationLevel,
-    pub years_in_higher_education: i32, // Amount of years the individual spent in college or university (TODO: use this)
+    pub years_in_higher_education: i32, // Amount of years the individual spent in college or university
+    pub studying_for: Option<EducationLevel>, // Education level the individual is in higher education for
     pub job: Job,
     pub debts: Vec<Debt>,
     pub monthly_debt_cost: f32,
+    pub credit_score: i32, // Out of 100, missed payments and defaults lower it
     pub years_in_unemployment: i32,
+    pub career_salary: i32, // Average salary over the individual's working life
+    pub career_months: i32,
 
     pub age: i32,
     pub birthday: Birthday,
@@ -83,9 +92,9 @@ pub struct Person {
     pub spending_behaviour: SpendingBehaviour,
     pub daily_food_spending: i32,
 
-    pub demand: HashMap<ProductType, f32>,
-    pub business_this_month: Option<Uuid>, // The business the individual will buy from this month, until marketing is re-evaluated
-    pub purchase_days: HashMap<i32, i32>, // The days of the month that they will make a purchase - <day, quantity>
+    pub demand: BTreeMap<ProductType, f32>,
+    pub businesses_this_month: BTreeMap<ProductType, Uuid>, // The business the individual will buy each product from this month, until marketing is re-evaluated
+    pub purchase_days: BTreeMap<ProductType, HashMap<i32, i32>>, // The days of the month that they will make a purchase of each product - <day, quantity>
 
     pub health_percentage: i32, // The percentage of their health that they have remaining
     pub hospitalisation_percentage: i32, // The percentage of their health that will require them to be hospitalised
@@ -94,8 +103,14 @@ pub struct Person {
     pub days_left_in_hospital: Option<i32>, // Days left that the person is in hospitalisation
     pub hospitalised_age: i32,           // This is important to avoid capacity bugs
     pub maximum_health: i32,
+    pub infection: Infection,
 
-    pub homeless: bool,
+    pub housing: Housing,
+    pub household_id: Option<Uuid>,
+    pub partner_id: Option<Uuid>,
+    pub parent_ids: Vec<Uuid>,
+    pub child_ids: Vec<Uuid>,
+    pub neglected: bool, // Children whose household could not provide for them this month
 
     pub gender: Gender,
 
@@ -110,52 +125,62 @@ impl Person {
     /// Generates a randomly aged person based on statistics
     pub fn new_generate(
         config: &Config,
-        product_demand: &mut HashMap<ProductType, f32>,
+        product_demand: &mut BTreeMap<ProductType, f32>,
         tax_rate: f32,
         tax_rule: &TaxRule,
+        income_tax: &IncomeTaxState,
         date: Date,
+        rng: &mut GameRng,
     ) -> IncResult<Self> {
         let mut person = Self {
-            id: Uuid::new_v4(),
-            age: Self::generate_age(),
-            birthday: Birthday::generate(),
+            id: generate_id(rng),
+            age: Self::generate_age(rng),
+            birthday: Birthday::generate(rng),
+            credit_score: STARTING_CREDIT_SCORE,
             ..Self::default()
         };
 
-        person.generate_gender(date);
+        person.generate_gender(date, rng);
 
-        person.education_level = generate_education_level(config);
+        // Children work their way through school as they grow up
+        if person.age >= 18 {
+            person.education_level = generate_education_level(config, rng);
+        }
         person.expected_salary_range = get_expected_salary_range(config, &person.education_level);
 
         let mut expected_salary =
-            ((person.expected_salary_range.start + person.expected_salary_range.end) / 2) as i32;
-        if person.age >= 70 || (person.age >= 65 && chance_one_in(60)) {
-            // Retired
-            person.job = Job::Retired;
-            person.set_salary(15800);
-            expected_salary = 15800;
+            (person.expected_salary_range.start + person.expected_salary_range.end) / 2;
+        if person.age >= 18 {
+            person.career_salary = expected_salary;
+            person.career_months = (person.age - 18) * 12;
         }
 
-        let tax_rate = Self::get_tax_rate(tax_rule, tax_rate, expected_salary);
+        if person.age >= config.pension.retirement_age {
+            person.retire(config);
+            expected_salary = person.salary;
+        }
+
+        let tax_rate = Self::get_tax_rate(income_tax, tax_rule, tax_rate, expected_salary);
 
-        person.generate_spending_behaviour();
-        person.generate_balance(expected_salary);
+        person.generate_spending_behaviour(rng);
+        person.generate_balance(expected_salary, rng);
 
         if person.age >= 18 {
-            person.generate_daily_food_spending();
-            person.debts = Debt::generate(&mut person, expected_salary as i32);
+            person.generate_daily_food_spending(rng);
+            person.debts = Debt::generate(&mut person, expected_salary, rng);
             person.get_monthly_debt_cost();
         } else {
             person.daily_food_spending = 0;
         }
 
-        person.calculate_demand(expected_salary, Some(product_demand), tax_rate)?;
-        person.generate_health();
+        person.calculate_demand(config, expected_salary, Some(product_demand), tax_rate, rng)?;
+        person.generate_health(rng);
 
         Ok(person)
     }
 
-    pub fn get_tax_rate(rule: &TaxRule, standard_tax_rate: f32, salary: i32) -> f32 {
+    /// Rate paid on income between the personal allowance and the first tax bracket
+    pub fn get_basic_tax_rate(rule: &TaxRule, standard_tax_rate: f32, salary: i32) -> f32 {
         if rule.enabled && salary >= rule.minimum_salary {
             return rule.tax_rate;
         }
@@ -163,62 +188,79 @@ impl Person {
         standard_tax_rate
     }
 
+    /// Share of the salary paid in income tax across every band
+    pub fn get_tax_rate(
+        income_tax: &IncomeTaxState,
+        rule: &TaxRule,
+        standard_tax_rate: f32,
+        salary: i32,
+    ) -> f32 {
+        income_tax.get_tax_rate(
+            Self::get_basic_tax_rate(rule, standard_tax_rate, salary),
+            salary,
+        )
+    }
+
     /// Adds a new baby to the population
     pub fn new_infant(
         config: &Config,
         tax_rate: f32,
         tax_rule: &TaxRule,
+        income_tax: &IncomeTaxState,
         date: Date,
+        rng: &mut GameRng,
     ) -> IncResult<Self> {
         let mut infant = Self {
-            id: Uuid::new_v4(),
+            id: generate_id(rng),
             birthday: Birthday::from(&date),
             health_percentage: 100,
             hospitalisation_percentage: 8,
             maximum_health: 100,
+            credit_score: STARTING_CREDIT_SCORE,
             ..Self::default()
         };
 
-        infant.generate_gender(date);
+        infant.generate_gender(date, rng);
 
-        infant.education_level = generate_education_level(config);
         infant.expected_salary_range = get_expected_salary_range(config, &infant.education_level);
 
         let expected_salary =
-            ((infant.expected_salary_range.start + infant.expected_salary_range.end) / 2) as i32;
-        let tax_rate = Self::get_tax_rate(tax_rule, tax_rate, expected_salary);
+            (infant.expected_salary_range.start + infant.expected_salary_range.end) / 2;
+        let tax_rate = Self::get_tax_rate(income_tax, tax_rule, tax_rate, expected_salary);
 
-        infant.generate_spending_behaviour();
-        infant.calculate_demand(0, None, tax_rate)?;
+        infant.generate_spending_behaviour(rng);
+        infant.calculate_demand(config, 0, None, tax_rate, rng)?;
 
         Ok(infant)
     }
 
-    fn generate_age() -> i32 {
+    fn generate_age(rng: &mut GameRng) -> i32 {
         // This is not entirely accurate in order to avoid massive decrease at start of game
-        let mut rng = rand::thread_rng();
-
-        percentage_based_output_int::<i32>(hashmap! {
-            (rng.gen_range(0..=18)) => 33,
-            (rng.gen_range(19..=25)) => 12,
-            (rng.gen_range(26..=34)) => 16,
-            (rng.gen_range(35..=54)) => 35,
-            (rng.gen_range(55..=64)) => 2,
-            (rng.gen_range(65..=90)) => 2,
-        })
+        let ages = vec![
+            (rng.gen_range(0..=18), 33),
+            (rng.gen_range(19..=25), 12),
+            (rng.gen_range(26..=34), 16),
+            (rng.gen_range(35..=54), 35),
+            (rng.gen_range(55..=64), 2),
+            (rng.gen_range(65..=90), 2),
+        ];
+
+        percentage_based_output_int::<i32>(ages, rng)
     }
 }
 
 // Dynamic methods
 impl Person {
-    fn generate_gender(&mut self, date: Date) {
-        if percentage_chance(50.) {
+    fn generate_gender(&mut self, date: Date, rng: &mut GameRng) {
+        if percentage_chance(50., rng) {
             self.gender = Gender::Male;
         }
 
-        let mut rng = rand::thread_rng();
-
-        if date.is_generation_day() && self.age <= 35 && self.age >= 18 && percentage_chance(1.2) {
+        if date.is_generation_day()
+            && self.age <= 35
+            && self.age >= 18
+            && percentage_chance(1.2, rng)
+        {
             let day = rng.gen_range(1..=30);
             let month = rng.gen_range(1..=12);
 
@@ -226,7 +268,7 @@ impl Person {
             return self.gender = Gender::Female;
         }
 
-        if self.age <= 35 && percentage_chance(42.) {
+        if self.age <= 35 && percentage_chance(42., rng) {
             // 42% chance of having a newborn (based on real world statistic)
             let mut latest_birthday_year = date.year + (35 - self.age) - 1;
             if latest_birthday_year < 0 {
@@ -253,6 +295,7 @@ impl Person {
         date: &Date,
         healthcare: &mut HealthcareState,
         rules: &GameStateRules,
+        rng: &mut GameRng,
     ) -> bool {
         if self.gender == Gender::Male || self.birth_date.is_none() {
             return false;
@@ -263,56 +306,58 @@ impl Person {
             return false;
         }
 
-        self.remove_health(rand::thread_rng().gen_range(20..40), healthcare, rules);
+        let health_loss = rng.gen_range(20..40);
+        self.remove_health(health_loss, healthcare, rules, rng);
         self.days_until_death.is_none() // Returns false if the person died during birth
     }
 
-    fn generate_spending_behaviour(&mut self) {
+    fn generate_spending_behaviour(&mut self, rng: &mut GameRng) {
         if matches!(self.job, Job::BusinessOwner(_)) {
-            self.spending_behaviour = percentage_based_output_int::<SpendingBehaviour>(hashmap! {
-                SpendingBehaviour::One => 1,
-                SpendingBehaviour::Two => 4,
-                SpendingBehaviour::Three => 25,
-                SpendingBehaviour::Four => 70,
-            });
+            self.spending_behaviour = percentage_based_output_int::<SpendingBehaviour>(
+                vec![
+                    (SpendingBehaviour::One, 1),
+                    (SpendingBehaviour::Two, 4),
+                    (SpendingBehaviour::Three, 25),
+                    (SpendingBehaviour::Four, 70),
+                ],
+                rng,
+            );
 
             return;
         }
 
-        self.spending_behaviour = match self.education_level {
-            NoFormalEducation => percentage_based_output_int::<SpendingBehaviour>(hashmap! {
-                SpendingBehaviour::One => 75,
-                SpendingBehaviour::Two => 20,
-                SpendingBehaviour::Three => 4,
-                SpendingBehaviour::Four => 1,
-            }),
-
-            HighSchoolDiploma => percentage_based_output_int::<SpendingBehaviour>(hashmap! {
-                SpendingBehaviour::One => 20,
-                SpendingBehaviour::Two => 70,
-                SpendingBehaviour::Three => 9,
-                SpendingBehaviour::Four => 1,
-            }),
-
-            College | AssociateDegree => {
-                percentage_based_output_int::<SpendingBehaviour>(hashmap! {
-                    SpendingBehaviour::One => 3,
-                    SpendingBehaviour::Two => 10,
-                    SpendingBehaviour::Three => 82,
-                    SpendingBehaviour::Four => 5,
-                })
-            }
-
-            Bachelors | AdvancedDegree => {
-                percentage_based_output_int::<SpendingBehaviour>(hashmap! {
-                    SpendingBehaviour::One => 1,
-                    SpendingBehaviour::Two => 4,
-                    SpendingBehaviour::Three => 77,
-                    SpendingBehaviour::Four => 18,
-                })
-            }
+        let chances = match self.education_level {
+            NoFormalEducation => vec![
+                (SpendingBehaviour::One, 75),
+                (SpendingBehaviour::Two, 20),
+                (SpendingBehaviour::Three, 4),
+                (SpendingBehaviour::Four, 1),
+            ],
+
+            HighSchoolDiploma => vec![
+                (SpendingBehaviour::One, 20),
+                (SpendingBehaviour::Two, 70),
+                (SpendingBehaviour::Three, 9),
+                (SpendingBehaviour::Four, 1),
+            ],
+
+            College | AssociateDegree => vec![
+                (SpendingBehaviour::One, 3),
+                (SpendingBehaviour::Two, 10),
+                (SpendingBehaviour::Three, 82),
+                (SpendingBehaviour::Four, 5),
+            ],
+
+            Bachelors | AdvancedDegree => vec![
+                (SpendingBehaviour::One, 1),
+                (SpendingBehaviour::Two, 4),
+                (SpendingBehaviour::Three, 77),
+                (SpendingBehaviour::Four, 18),
+            ],
         };
 
+        self.spending_behaviour = percentage_based_output_int::<SpendingBehaviour>(chances, rng);
+
         self.saving_percentage_range = match self.spending_behaviour {
             SpendingBehaviour::One => 5..8,
             SpendingBehaviour::Two => 8..12,
@@ -321,7 +366,7 @@ impl Person {
         }
     }
 
-    fn generate_balance(&mut self, salary: i32) {
+    fn generate_balance(&mut self, salary: i32, rng: &mut GameRng) {
         // TODO: Vary on spending behaviour
 
         if self.age >= 18 {
@@ -330,29 +375,32 @@ impl Person {
                     We calculate the average % of salary U.S citizens have in their bank account with ((average_salary * us_population) / us_gdp) * 100
                     This evaluated to 107%, have added a 50% leeway which gives us a range between 53.5% and 214% of the individuals salary
                 */
-                return self.balance = salary as f32 * float_range(0.535, 2.14, 3);
+                return self.balance = salary as f32 * float_range(0.535, 2.14, 3, rng);
             }
 
-            return self.balance = float_range(50., 1200., 1);
+            return self.balance = float_range(50., 1200., 1, rng);
         }
 
         // Default child's balance
-        self.balance = float_range(4., 90., 1);
+        self.balance = float_range(4., 90., 1, rng);
     }
 
+    /// Splits what the individual is willing to spend this month between the products
     pub fn calculate_demand(
         &mut self,
+        config: &Config,
         salary: i32,
-        product_demand: Option<&mut HashMap<ProductType, f32>>,
+        mut product_demand: Option<&mut BTreeMap<ProductType, f32>>,
         tax_rate: f32,
+        rng: &mut GameRng,
     ) -> IncResult<()> {
         if salary == 0 {
-            *self.demand.entry(ProductType::Leisure).or_insert(0.) = 0.;
+            for product_type in ProductType::ALL {
+                self.demand.insert(product_type, 0.);
+            }
             return Ok(());
         }
 
-        let mut rng = rand::thread_rng();
-
         let balance_percentage = match self.spending_behaviour {
             SpendingBehaviour::One => rng.gen_range(25..50),
             SpendingBehaviour::Two => rng.gen_range(10..30),
@@ -360,38 +408,61 @@ impl Person {
             SpendingBehaviour::Four => rng.gen_range(1..3),
         };
 
-        let remaining_balance = self.balance - (4. * 30.) - self.monthly_debt_cost;
+        let remaining_balance =
+            self.balance - (4. * 30.) - self.monthly_debt_cost - self.get_monthly_rent() as f32;
         let mut total_demand = remaining_balance * as_decimal_percent!(balance_percentage);
         total_demand -= (salary / 12) as f32 * tax_rate;
         if total_demand < 0. {
             total_demand = 0.;
         }
 
-        *self
-            .demand
-            .entry(ProductType::Leisure)
-            .or_insert(total_demand) += total_demand;
+        let total_weight: f32 = ProductType::ALL
+            .iter()
+            .filter(|product_type| product_type.is_bought_on_purchase_days())
+            .map(|product_type| product_type.demand_weight(config, salary))
+            .sum();
 
-        if let Some(prod_dem) = product_demand {
-            let product_demand = prod_dem
-                .get_mut(&ProductType::Leisure)
-                .ok_or(Error::DangerUnexpected)?;
-            *product_demand += total_demand;
+        for product_type in ProductType::ALL {
+            // Food and rent are budgeted for before anything else, and paid when due rather than saved up for
+            let demand = match product_type {
+                ProductType::Food => {
+                    let demand = (self.daily_food_spending * 30) as f32;
+                    self.demand.insert(product_type, demand);
+                    demand
+                }
+                ProductType::Housing => {
+                    let demand = self.get_monthly_rent() as f32;
+                    self.demand.insert(product_type, demand);
+                    demand
+                }
+                _ => {
+                    let demand =
+                        total_demand * (product_type.demand_weight(config, salary) / total_weight);
+                    *self.demand.entry(product_type).or_insert(0.) += demand;
+                    demand
+                }
+            };
+
+            if let Some(prod_dem) = product_demand.as_deref_mut() {
+                *prod_dem.entry(product_type).or_insert(0.) += demand;
+            }
         }
 
         Ok(())
     }
 
-    pub fn calculate_daily_food_spending(&self) -> i32 {
+    /// Food is budgeted from the given funds, which are the household's when the individual
+    /// lives with other adults
+    pub fn calculate_daily_food_spending(&self, funds: f32, rng: &mut GameRng) -> i32 {
         let healthy_cost = self.monthly_debt_cost + (4 * 30) as f32;
         let survivable_cost = self.monthly_debt_cost + (3 * 30) as f32;
         let unhealthy_cost = self.monthly_debt_cost + (2 * 30) as f32;
 
-        if self.can_afford_bare(healthy_cost) {
+        if funds - healthy_cost > 0. {
             return 4;
-        } else if self.can_afford_bare(survivable_cost) {
+        } else if funds - survivable_cost > 0. {
             return 3;
-        } else if self.can_afford_bare(unhealthy_cost) {
+        } else if funds - unhealthy_cost > 0. {
             let (action_one_chance, action_two_chance) = match self.spending_behaviour {
                 SpendingBehaviour::One => (90, 10),
                 SpendingBehaviour::Two => (55, 45),
@@ -399,10 +470,10 @@ impl Person {
                 SpendingBehaviour::Four => (10, 90),
             };
 
-            let action = percentage_based_output_int(hashmap! {
-                1 => action_one_chance,
-                2 => action_two_chance,
-            });
+            let action = percentage_based_output_int(
+                vec![(1, action_one_chance), (2, action_two_chance)],
+                rng,
+            );
 
             if action == 1 {
                 return 3;
@@ -411,7 +482,7 @@ impl Person {
             }
         }
 
-        if self.balance <= 0. {
+        if funds <= 0. {
             0
         } else {
             1
@@ -419,21 +490,58 @@ impl Person {
     }
 
     /// This should be done every time the individual's salary changes, and every month.
-    pub fn generate_daily_food_spending(&mut self) {
+    pub fn generate_daily_food_spending(&mut self, rng: &mut GameRng) {
         // TODO: change me
         if let Job::BusinessOwner(_) = self.job {
             return self.daily_food_spending = 4;
         }
 
         if self.job == Job::Unemployed {
-            self.set_salary(generate_unemployed_salary()); // TODO: make me more dynamic & move me
+            self.set_salary(generate_unemployed_salary(rng)); // TODO: make me more dynamic & move me
+        }
+
+        self.daily_food_spending = self.calculate_daily_food_spending(self.balance, rng)
+    }
+
+    /// Pays for the day's food at the food business picked this month, with sales tax on top.
+    /// Food is still bought when no business sells it, the money just leaves the economy.
+    /// Returns what the food cost
+    fn buy_food(
+        &mut self,
+        businesses: &mut BTreeMap<Uuid, Business>,
+        sales_tax_rule: &SalesTaxRule,
+        sales_tax: &mut f64,
+    ) -> f32 {
+        let spending = self.daily_food_spending as f32;
+        let business = self
+            .businesses_this_month
+            .get(&ProductType::Food)
+            .and_then(|bid| businesses.get_mut(bid));
+
+        let business = match business {
+            Some(business) => business,
+            None => {
+                self.balance -= spending;
+                return spending;
+            }
+        };
+
+        let tax = spending * Business::get_sales_tax_rate(sales_tax_rule, ProductType::Food);
+        self.balance -= spending + tax;
+        business.balance += spending as f64;
+        *sales_tax += tax as f64;
+
+        // Each day of food counts as one sale, a day without food is a sale the business missed
+        if spending > 0. {
+            business.units_sold_this_month += 1;
+        } else {
+            business.units_missed_this_month += 1;
         }
 
-        self.daily_food_spending = self.calculate_daily_food_spending()
+        spending + tax
     }
 
-    pub fn can_afford(&self, price: f32) -> bool {
-        let mut rng = rand::thread_rng();
+    pub fn can_afford(&self, price: f32, rng: &mut GameRng) -> bool {
         let saving_percent = rng.gen_range(self.saving_percentage_range.clone()) as f32 / 100.;
 
         let mut cut_balance: f32 = self.balance
@@ -462,25 +570,62 @@ impl Person {
         *government_balance += amount as i64;
     }
 
-    pub fn check_birthday(&mut self, date: &Date) {
+    pub fn check_birthday(
+        &mut self,
+        date: &Date,
+        config: &Config,
+        education: &mut EducationState,
+        rules: &GameStateRules,
+        rng: &mut GameRng,
+    ) {
         if date.day == self.birthday.day && date.month == self.birthday.month {
-            self.grow_up();
+            self.grow_up(config, education, rules, rng);
         }
     }
 
+    /// The government pays for the day's food of people covered by a food rule, until the rule's
+    /// people count is reached. Each rule counts only the people it covered
+    fn claim_food_coverage(
+        &mut self,
+        rules: &GameStateRules,
+        food_coverage: &mut i32,
+        unemployed_food_coverage: &mut i32,
+    ) {
+        if rules.cover_food_rule.enabled
+            && self.salary < rules.cover_food_rule.maximum_salary
+            && *food_coverage < rules.cover_food_rule.people_count
+        {
+            self.balance += 4.;
+            *food_coverage += 1;
+        } else if rules.cover_food_unemployed_rule.enabled
+            && self.job == Job::Unemployed
+            && *unemployed_food_coverage < rules.cover_food_unemployed_rule.people_count
+        {
+            self.balance += 4.;
+            *unemployed_food_coverage += 1;
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn day_pass(
         &mut self,
         day: i32,
         healthcare: &mut HealthcareState,
+        education: &mut EducationState,
         date: &Date,
-        businesses: &mut HashMap<Uuid, Business>,
+        businesses: &mut BTreeMap<Uuid, Business>,
         purchases: &mut u32,
         total_possible_purchases: &mut u32,
+        sales_tax: &mut f64,
+        household_balance: Option<f32>,
+        food_bill: &mut f32,
         rules: &GameStateRules,
         food_coverage: &mut i32,
         unemployed_food_coverage: &mut i32,
+        config: &Config,
+        rng: &mut GameRng,
     ) -> IncResult<bool> {
-        self.check_birthday(date);
+        self.check_birthday(date, config, education, rules, rng);
 
         if let Some(ref mut days) = self.days_until_death {
             *days -= 1;
@@ -489,42 +634,22 @@ impl Person {
             }
         }
 
-        let mut rng = rand::thread_rng();
-
-        if !self.homeless && self.age >= 18 && self.job == Job::Unemployed {
-            // TODO: affect this by other factors
-            if chance_one_in(500 * 365) {
-                // 1 in 500 chance each year
-                self.homeless = true;
-            }
-        }
-
-        if chance_one_in(7300) {
+        if chance_one_in(7300, rng) {
             // Average person has minor accident every 20 years (guessed)
-            self.remove_health(rng.gen_range(15..=25), healthcare, rules);
+            let health_loss = rng.gen_range(15..=25);
+            self.remove_health(health_loss, healthcare, rules, rng);
         }
 
-        if self.homeless {
+        if self.is_homeless() {
             self.balance += rng.gen_range(1..=2) as f32;
         }
 
-        if rules.cover_food_rule.enabled
-            && self.salary < rules.cover_food_rule.maximum_salary
-            && *food_coverage <= rules.cover_food_rule.people_count
-        {
-            self.balance += 4.;
-            *food_coverage += 1;
-        } else if rules.cover_food_unemployed_rule.enabled
-            && self.job == Job::Unemployed
-            && *unemployed_food_coverage <= rules.cover_food_unemployed_rule.people_count
-        {
-            self.balance += 4.;
-            *food_coverage += 1;
-        }
+        self.claim_food_coverage(rules, food_coverage, unemployed_food_coverage);
 
         if self.age >= 18 && !matches!(self.job, Job::BusinessOwner(_)) {
-            self.daily_food_spending = self.calculate_daily_food_spending();
-            self.balance -= self.daily_food_spending as f32;
+            let funds = household_balance.unwrap_or(self.balance);
+            self.daily_food_spending = self.calculate_daily_food_spending(funds, rng);
+            *food_bill = self.buy_food(businesses, &rules.sales_tax_rule, sales_tax);
 
             let (health_loss_chance, welfare_loss) = match self.daily_food_spending {
                 // Chance that the individual will lose 1% of their health
@@ -540,13 +665,17 @@ impl Person {
             self.welfare_machine
                 .remove_welfare_if(welfare_loss, day, welfare_loss != 0);
 
-            if percentage_chance(health_loss_chance) {
-                self.remove_health(1, healthcare, rules);
+            if percentage_chance(health_loss_chance, rng) {
+                self.remove_health(1, healthcare, rules, rng);
             }
         }
 
         let mut in_hospital = false;
 
+        if self.days_left_in_hospital.is_some() {
+            self.bill_hospital_day(&rules.hospital_charges_rule, healthcare);
+        }
+
         if let Some(ref mut days) = self.days_left_in_hospital {
             in_hospital = true;
 
@@ -560,23 +689,50 @@ impl Person {
 
         self.welfare_machine
             .remove_welfare_if(WELFARE_IMPACT_THREE, day, in_hospital);
-        self.replenish_health();
+        self.welfare_machine
+            .remove_welfare_if(WELFARE_IMPACT_FIVE, day, self.is_homeless());
+        self.welfare_machine
+            .remove_welfare_if(WELFARE_IMPACT_FOUR, day, self.neglected);
+        self.welfare_machine.remove_welfare_if(
+            WELFARE_IMPACT_THREE,
+            day,
+            self.credit_score < POOR_CREDIT_SCORE,
+        );
+        self.replenish_health(rng);
 
-        let quantity_opt = self.purchase_days.remove(&day);
         let mut not_afford_wanted_item = false;
 
-        if let Some(quantity) = quantity_opt {
-            let business_this_month = &self.business_this_month.ok_or(Error::DangerUnexpected)?;
+        for product_type in ProductType::ALL {
+            if !product_type.is_bought_on_purchase_days() {
+                continue;
+            }
+
+            let quantity_opt = self
+                .purchase_days
+                .get_mut(&product_type)
+                .and_then(|days| days.remove(&day));
+
+            let quantity = match quantity_opt {
+                Some(quantity) => quantity,
+                None => continue,
+            };
+
+            let business_this_month = self
+                .businesses_this_month
+                .get(&product_type)
+                .ok_or(Error::DangerUnexpected)?;
             let business = businesses.get_mut(business_this_month).ok_or_else(|| {
                 Error::Warning(
                     "Could not find business that was expected to purchase from.".to_string(),
                 )
             })?;
-            let item_cost = (business.product_price * quantity) as f32;
+            let sales_tax_rate = Business::get_sales_tax_rate(&rules.sales_tax_rule, product_type);
+            let item_cost = business.get_shelf_price(sales_tax_rate);
+            let item_tax = item_cost - business.product_price as f32;
             *total_possible_purchases += quantity as u32;
 
-            for _ in 0..quantity {
-                if self.can_afford(item_cost) {
+            for attempt in 0..quantity {
+                if self.can_afford(item_cost, rng) {
                     *purchases += 1;
 
                     self.balance -= item_cost;
@@ -589,17 +745,20 @@ impl Person {
                         *demand = 0.
                     }
 
-                    business.balance += item_cost as f64;
+                    business.balance += (item_cost - item_tax) as f64;
+                    business.units_sold_this_month += 1;
+                    *sales_tax += item_tax as f64;
                     self.welfare_machine
                         .add_welfare_if(WELFARE_IMPACT_TWO, day, true);
                 } else {
+                    business.units_missed_this_month += quantity - attempt;
                     not_afford_wanted_item = true;
                     break;
                 }
             }
         }
 
-        let no_business_this_month = self.business_this_month.is_none();
+        let no_business_this_month = self.businesses_this_month.is_empty();
 
         self.welfare_machine
             .remove_welfare_if(WELFARE_IMPACT_THREE, day, not_afford_wanted_item);
@@ -640,7 +799,7 @@ impl Person {
 }
 
 // MUST be ordered from lowest to highest
-#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EducationLevel {
     #[default]
     NoFormalEducation,
@@ -651,7 +810,7 @@ pub enum EducationLevel {
     AdvancedDegree,
 }
 
-#[derive(Default, Clone, PartialEq, Eq)]
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Job {
     BusinessOwner(Uuid), // usize refers to index of the business in the game state
     Employee(Uuid),
@@ -660,7 +819,7 @@ pub enum Job {
     Unemployed,
 }
 
-#[derive(Default, Clone, PartialEq, Eq, Hash)]
+#[derive(Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SpendingBehaviour {
     #[default]
     One,
@@ -668,3 +827,116 @@ pub enum SpendingBehaviour {
     Three,
     Four,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::load_config;
+    use crate::game::structs::{CoverFoodRule, CoverFoodUnemployedRule};
+    use rand::SeedableRng;
+
+    fn adult(balance: f32, daily_food_spending: i32) -> Person {
+        Person {
+            id: Uuid::new_v4(),
+            age: 30,
+            balance,
+            daily_food_spending,
+            ..Person::default()
+        }
+    }
+
+    #[test]
+    fn demand_is_split_between_products_by_weight() {
+        let config = load_config().unwrap();
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut per = adult(10000., 4);
+        let mut product_demand = BTreeMap::new();
+
+        per.calculate_demand(&config, 40000, Some(&mut product_demand), 0.2, &mut rng)
+            .unwrap();
+
+        assert_eq!(per.demand[&ProductType::Food], 120.);
+        assert_eq!(product_demand, per.demand);
+
+        let leisure_demand = per.demand[&ProductType::Leisure];
+        let leisure_share = leisure_demand / ProductType::Leisure.demand_weight(&config, 40000);
+        let furniture_share = per.demand[&ProductType::Furniture]
+            / ProductType::Furniture.demand_weight(&config, 40000);
+        assert!(leisure_share > 0.);
+        assert!((leisure_share - furniture_share).abs() < 0.01);
+
+        // Unspent demand carries over, food is budgeted again from scratch
+        per.calculate_demand(&config, 40000, None, 0.2, &mut rng)
+            .unwrap();
+        assert_eq!(per.demand[&ProductType::Food], 120.);
+        assert!(per.demand[&ProductType::Leisure] > leisure_demand);
+    }
+
+    #[test]
+    fn food_rules_count_only_the_people_they_cover() {
+        let rules = GameStateRules {
+            cover_food_rule: CoverFoodRule {
+                enabled: true,
+                people_count: 1,
+                maximum_salary: 20000,
+                budget_cost: 0,
+            },
+            cover_food_unemployed_rule: CoverFoodUnemployedRule {
+                enabled: true,
+                people_count: 1,
+                budget_cost: 0,
+            },
+            ..GameStateRules::default()
+        };
+        let (mut food_coverage, mut unemployed_food_coverage) = (0, 0);
+
+        // Both are unemployed, the first is covered for their low salary and the second for being unemployed
+        let mut people = [adult(0., 0), adult(0., 0), adult(0., 0)];
+        for per in people.iter_mut() {
+            per.salary = 10000;
+            per.claim_food_coverage(&rules, &mut food_coverage, &mut unemployed_food_coverage);
+        }
+
+        assert_eq!(food_coverage, 1);
+        assert_eq!(unemployed_food_coverage, 1);
+        assert_eq!(people.map(|per| per.balance), [4., 4., 0.]);
+    }
+
+    #[test]
+    fn food_is_budgeted_from_the_funds_available() {
+        let mut rng = GameRng::seed_from_u64(0);
+        let per = adult(0., 0);
+
+        assert_eq!(per.calculate_daily_food_spending(0., &mut rng), 0);
+        assert_eq!(per.calculate_daily_food_spending(1000., &mut rng), 4);
+        assert_eq!(per.calculate_daily_food_spending(100., &mut rng), 3);
+    }
+
+    #[test]
+    fn food_is_bought_from_the_food_business() {
+        let mut per = adult(100., 4);
+        let business = Business {
+            id: Uuid::new_v4(),
+            product_type: ProductType::Food,
+            ..Business::default()
+        };
+        per.businesses_this_month
+            .insert(ProductType::Food, business.id);
+
+        let mut businesses = BTreeMap::from([(business.id, business)]);
+        let rule = SalesTaxRule {
+            enabled: true,
+            food: 0.5,
+            ..SalesTaxRule::default()
+        };
+        let mut sales_tax = 0.;
+
+        assert_eq!(per.buy_food(&mut businesses, &rule, &mut sales_tax), 6.);
+
+        let business = businesses.values().next().unwrap();
+        assert_eq!(per.balance, 94.);
+        assert_eq!(business.balance, 4.);
+        assert_eq!(business.units_sold_this_month, 1);
+        assert_eq!(sales_tax, 2.);
+    }
+}
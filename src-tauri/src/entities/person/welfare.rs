@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 pub const WELFARE_IMPACT_ONE: i32 = 2;
 pub const WELFARE_IMPACT_TWO: i32 = 5;
 pub const WELFARE_IMPACT_THREE: i32 = 10;
@@ -6,14 +8,14 @@ pub const WELFARE_IMPACT_FIVE: i32 = 45;
 pub const WELFARE_IMPACT_SIX: i32 = 70;
 pub const WELFARE_IMPACT_SEVEN: i32 = 100;
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct WelfareDay {
     pub maximum: i32, // Maximum possible welfare for that day
     pub minimum: i32,
     pub amount: i32, // Welfare gained that day
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WelfareMachine {
     pub welfare_days: Vec<WelfareDay>,
 }
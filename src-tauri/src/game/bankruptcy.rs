@@ -0,0 +1,318 @@
+use uuid::Uuid;
+
+use super::{
+    sink::{BankruptcyReason, GameEvent, GameEventSink},
+    structs::{BusinessFundingRule, GameState},
+};
+use crate::{
+    common::{
+        errors::{Error, IncResult},
+        util::generate_unemployed_salary,
+    },
+    entities::{business::Business, person::person::Job},
+};
+
+const GRACE_MONTHS: i32 = 3; // Months a business can stay in debt before it is bailed out or liquidated
+const LAYOFF_PERCENTAGE: f32 = 0.2; // Staff laid off each month the business is in debt
+const ASSET_RECOVERY: f64 = 0.25; // Part of a month's expected income raised by selling off the business
+
+/// Pays off the debt of a business that ran out of time, taking up one of the places the
+/// business funding rule pays for
+fn bail_out(
+    rule: &BusinessFundingRule,
+    business: &mut Business,
+    funded_businesses: &mut i32,
+) -> Result<(), BankruptcyReason> {
+    if !rule.enabled || *funded_businesses >= rule.business_count {
+        return Err(BankruptcyReason::NoBailout);
+    }
+
+    if business.balance + rule.fund as f64 <= 0. {
+        return Err(BankruptcyReason::DebtTooLarge);
+    }
+
+    business.balance += rule.fund as f64;
+    business.months_in_debt = 0;
+    *funded_businesses += 1;
+
+    Ok(())
+}
+
+impl GameState {
+    /// Businesses that end the month with no money lay off some of their staff to cut costs.
+    /// Once the grace period is over they are bailed out or liquidated
+    pub fn check_bankruptcies(
+        &mut self,
+        funded_businesses: &mut i32,
+        sink: &dyn GameEventSink,
+    ) -> IncResult<()> {
+        let mut indebted: Vec<Uuid> = Vec::new();
+
+        for business in self.businesses.values_mut() {
+            if business.balance > 0. {
+                business.months_in_debt = 0;
+                continue;
+            }
+
+            business.months_in_debt += 1;
+            indebted.push(business.id);
+        }
+
+        for bid in indebted {
+            let business = self
+                .businesses
+                .get_mut(&bid)
+                .ok_or(Error::DangerUnexpected)?;
+
+            if business.months_in_debt <= GRACE_MONTHS {
+                let layoffs = (business.employees.len() as f32 * LAYOFF_PERCENTAGE).ceil() as i32;
+                business.remove_employees(layoffs, &mut self.people, &mut self.rng)?;
+
+                continue;
+            }
+
+            if let Err(reason) = bail_out(
+                &self.rules.business_funding_rule,
+                business,
+                funded_businesses,
+            ) {
+                self.liquidate_business(bid, reason, sink)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sells off what is left of the business. Staff are paid a final month's wage first, then the
+    /// government, which lends to businesses through bail-outs, is repaid their debt. The owner
+    /// only keeps what is left after that
+    fn liquidate_business(
+        &mut self,
+        bid: Uuid,
+        reason: BankruptcyReason,
+        sink: &dyn GameEventSink,
+    ) -> IncResult<()> {
+        let business = self.businesses.get(&bid).ok_or(Error::DangerUnexpected)?;
+
+        let mut assets =
+            business.balance.max(0.) + business.expected_income.max(0) as f64 * ASSET_RECOVERY;
+        let final_pay = match business.employees.len() {
+            0 => 0.,
+            count => (business.employee_salary as f64 / 12.).min(assets / count as f64),
+        };
+        assets -= final_pay * business.employees.len() as f64;
+
+        for employee_id in &business.employees {
+            if let Some(employee) = self.people.get_mut(employee_id) {
+                employee.balance += final_pay as f32;
+            }
+        }
+
+        let repaid = (-business.balance).max(0.).min(assets);
+        self.government_balance += repaid as i64;
+        assets -= repaid;
+
+        if let Some(owner) = self.people.get_mut(&business.owner_id) {
+            owner.balance += assets as f32;
+            owner.job = Job::Unemployed;
+            owner.set_salary(generate_unemployed_salary(&mut self.rng));
+        }
+
+        let business = self.close_business(bid).ok_or(Error::DangerUnexpected)?;
+
+        sink.emit(GameEvent::BusinessBankrupt {
+            business_id: bid,
+            product_type: business.product_type,
+            laid_off: business.employees.len(),
+            reason,
+        })
+    }
+
+    /// Removes the business from the game, its staff become unemployed and nobody can buy from
+    /// it for the rest of the month
+    pub fn close_business(&mut self, bid: Uuid) -> Option<Business> {
+        let business = self.businesses.remove(&bid)?;
+
+        for employee_id in &business.employees {
+            if let Some(employee) = self.people.get_mut(employee_id) {
+                employee.job = Job::Unemployed;
+                employee.set_salary(generate_unemployed_salary(&mut self.rng));
+            }
+        }
+
+        for per in self.people.values_mut() {
+            if per.businesses_this_month.get(&business.product_type) == Some(&bid) {
+                per.businesses_this_month.remove(&business.product_type);
+                per.purchase_days.remove(&business.product_type);
+            }
+        }
+
+        Some(business)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{entities::person::person::Person, game::sink::RecordingEventSink};
+
+    #[test]
+    fn indebted_business_is_liquidated_after_the_grace_period() {
+        let mut state = GameState::default();
+
+        let bid = Uuid::new_v4();
+        let employee = Person {
+            id: Uuid::new_v4(),
+            age: 30,
+            job: Job::Employee(bid),
+            ..Person::default()
+        };
+        let employee_id = employee.id;
+        state.people.insert(employee_id, employee);
+        state.businesses.insert(
+            bid,
+            Business {
+                id: bid,
+                balance: -100.,
+                employee_salary: 12000,
+                expected_income: 40000,
+                employees: vec![employee_id],
+                ..Business::default()
+            },
+        );
+
+        let sink = RecordingEventSink::default();
+        for _ in 0..GRACE_MONTHS {
+            state.check_bankruptcies(&mut 0, &sink).unwrap();
+        }
+
+        // The only employee was laid off while the business was in debt
+        assert!(state.businesses.contains_key(&bid));
+        assert!(state.people[&employee_id].job == Job::Unemployed);
+
+        state.check_bankruptcies(&mut 0, &sink).unwrap();
+        assert!(state.businesses.is_empty());
+
+        let events = sink.take_events();
+        assert!(matches!(
+            events.as_slice(),
+            [GameEvent::BusinessBankrupt {
+                reason: BankruptcyReason::NoBailout,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn creditors_are_paid_before_the_owner() {
+        let mut state = GameState::default();
+        let government_balance = state.government_balance;
+
+        let owner = Person {
+            id: Uuid::new_v4(),
+            ..Person::default()
+        };
+        let owner_id = owner.id;
+        state.people.insert(owner_id, owner);
+
+        // Selling off the business raises 10000, it owes 4000 in final wages and 10000 of debt
+        let mut business = Business {
+            id: Uuid::new_v4(),
+            owner_id,
+            balance: -10000.,
+            employee_salary: 24000,
+            expected_income: 40000,
+            ..Business::default()
+        };
+        for _ in 0..2 {
+            let employee = Person {
+                id: Uuid::new_v4(),
+                job: Job::Employee(business.id),
+                ..Person::default()
+            };
+            business.employees.push(employee.id);
+            state.people.insert(employee.id, employee);
+        }
+        let employees = business.employees.clone();
+        let bid = business.id;
+        state.businesses.insert(bid, business);
+
+        state
+            .liquidate_business(
+                bid,
+                BankruptcyReason::NoBailout,
+                &RecordingEventSink::default(),
+            )
+            .unwrap();
+
+        assert!(employees
+            .iter()
+            .all(|emp_id| state.people[emp_id].balance == 2000.));
+        assert_eq!(state.government_balance, government_balance + 6000);
+        assert_eq!(state.people[&owner_id].balance, 0.);
+        assert!(state.people[&owner_id].job == Job::Unemployed);
+    }
+
+    #[test]
+    fn owner_keeps_what_is_left_once_the_debt_is_repaid() {
+        let mut state = GameState::default();
+        let government_balance = state.government_balance;
+
+        let owner = Person {
+            id: Uuid::new_v4(),
+            ..Person::default()
+        };
+        let owner_id = owner.id;
+        state.people.insert(owner_id, owner);
+
+        let business = Business {
+            id: Uuid::new_v4(),
+            owner_id,
+            balance: -1000.,
+            expected_income: 40000,
+            ..Business::default()
+        };
+        let bid = business.id;
+        state.businesses.insert(bid, business);
+
+        state
+            .liquidate_business(
+                bid,
+                BankruptcyReason::NoBailout,
+                &RecordingEventSink::default(),
+            )
+            .unwrap();
+
+        assert_eq!(state.government_balance, government_balance + 1000);
+        assert_eq!(state.people[&owner_id].balance, 9000.);
+    }
+
+    #[test]
+    fn business_funding_bails_out_small_debts() {
+        let mut state = GameState::default();
+        state.rules.business_funding_rule = BusinessFundingRule {
+            enabled: true,
+            fund: 500,
+            business_count: 1,
+            ..BusinessFundingRule::default()
+        };
+
+        let business = Business {
+            id: Uuid::new_v4(),
+            balance: -100.,
+            months_in_debt: GRACE_MONTHS,
+            ..Business::default()
+        };
+        let bid = business.id;
+        state.businesses.insert(bid, business);
+
+        let funded_businesses = &mut 0;
+        state
+            .check_bankruptcies(funded_businesses, &RecordingEventSink::default())
+            .unwrap();
+
+        assert_eq!(*funded_businesses, 1);
+        assert_eq!(state.businesses[&bid].balance, 400.);
+        assert_eq!(state.businesses[&bid].months_in_debt, 0);
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use std::{sync::Mutex, time::Duration};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio_util::sync::CancellationToken;
+
+use crate::common::errors::{Error, IncResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameSpeed {
+    #[serde(rename = "1x")]
+    Normal,
+    #[serde(rename = "2x")]
+    Double,
+    #[serde(rename = "5x")]
+    Fast,
+    #[serde(rename = "max")]
+    Max,
+}
+
+impl GameSpeed {
+    /// How long a game day lasts, `None` runs days back to back
+    pub const fn day_duration(self) -> Option<Duration> {
+        match self {
+            GameSpeed::Normal => Some(Duration::from_millis(1000)),
+            GameSpeed::Double => Some(Duration::from_millis(500)),
+            GameSpeed::Fast => Some(Duration::from_millis(200)),
+            GameSpeed::Max => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlMessage {
+    Pause,
+    Resume,
+    SetSpeed(GameSpeed),
+    StepDay, // Runs a single day, even while paused
+}
+
+/// How the running game loop is currently being driven
+pub struct LoopControl {
+    pub paused: bool,
+    pub speed: GameSpeed,
+}
+
+impl Default for LoopControl {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            speed: GameSpeed::Normal,
+        }
+    }
+}
+
+impl LoopControl {
+    /// Returns true if a day should be run straight away because of the message
+    pub fn apply(&mut self, message: ControlMessage) -> bool {
+        match message {
+            ControlMessage::Pause => self.paused = true,
+            ControlMessage::Resume => self.paused = false,
+            ControlMessage::SetSpeed(speed) => self.speed = speed,
+            ControlMessage::StepDay => return true,
+        }
+
+        false
+    }
+}
+
+/// What a game loop is given to be controlled with
+pub struct LoopReceiver {
+    pub messages: UnboundedReceiver<ControlMessage>,
+    pub cancel: CancellationToken,
+}
+
+struct RunningLoop {
+    sender: UnboundedSender<ControlMessage>,
+    cancel: CancellationToken,
+}
+
+impl RunningLoop {
+    /// The receiver is dropped when the loop finishes, so a closed channel means the loop has stopped
+    fn is_running(&self) -> bool {
+        !self.cancel.is_cancelled() && !self.sender.is_closed()
+    }
+}
+
+/// Controls the running game loop, managed by Tauri so that commands can reach it
+#[derive(Default)]
+pub struct GameControl {
+    running: Mutex<Option<RunningLoop>>,
+}
+
+impl GameControl {
+    /// Registers a new game loop, only one loop can run the game state at a time
+    pub fn start(&self) -> IncResult<LoopReceiver> {
+        let mut running = self.running.lock().unwrap();
+
+        if running.as_ref().is_some_and(RunningLoop::is_running) {
+            return Err(Error::Warning(
+                "A game is already running, it must be reset before starting another.".to_string(),
+            ));
+        }
+
+        let (sender, messages) = unbounded_channel();
+        let cancel = CancellationToken::new();
+
+        *running = Some(RunningLoop {
+            sender,
+            cancel: cancel.clone(),
+        });
+
+        Ok(LoopReceiver { messages, cancel })
+    }
+
+    /// Cancels the running game loop, it will not start another day after this returns
+    pub fn stop(&self) {
+        if let Some(running) = self.running.lock().unwrap().take() {
+            running.cancel.cancel();
+        }
+    }
+
+    pub fn send(&self, message: ControlMessage) -> IncResult<()> {
+        let running = self.running.lock().unwrap();
+
+        running
+            .as_ref()
+            .filter(|running| running.is_running())
+            .and_then(|running| running.sender.send(message).ok())
+            .ok_or_else(|| Error::Warning("There is no game running.".to_string()))
+    }
+}
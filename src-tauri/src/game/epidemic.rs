@@ -0,0 +1,250 @@
+use rand::Rng;
+use std::cmp::Reverse;
+use uuid::Uuid;
+
+use super::{
+    sink::{GameEvent, GameEventSink},
+    structs::GameState,
+};
+use crate::{
+    common::{errors::IncResult, util::chance_one_in},
+    entities::person::{
+        disease::Infection,
+        welfare::{WELFARE_IMPACT_THREE, WELFARE_IMPACT_TWO},
+    },
+};
+
+const OUTBREAK_CHANCE: i32 = 720; // An outbreak starts about every two years while nobody is infected
+const OUTBREAK_SIZE: usize = 5; // People infected when an outbreak starts
+const TRANSMISSION_RATE: f32 = 0.3; // Daily infections caused by each infectious person, if everyone could catch it
+const QUARANTINE_REDUCTION: f32 = 0.6; // Part of the spread stopped by people staying home
+pub const VACCINE_COST: i64 = 60;
+const EXPECTED_HEALTHCARE_SPENDING_PER_PERSON: f32 = 4000.; // Monthly healthcare budget per person for the disease to spread as usual
+const MINIMUM_HEALTHCARE_FUNDING: f32 = 0.5;
+const MAXIMUM_HEALTHCARE_FUNDING: f32 = 1.5;
+
+impl GameState {
+    /// Runs daily after everyone's day has passed. An outbreak may start while nobody is ill,
+    /// then the disease spreads from those who are infectious to those who are not immune,
+    /// more slowly when healthcare is well funded.
+    /// Vaccines are given out first so they protect people from the day's spread
+    pub fn spread_disease(&mut self, day: i32, sink: &dyn GameEventSink) -> IncResult<()> {
+        if self.epidemic.infected_count == 0 && chance_one_in(OUTBREAK_CHANCE, &mut self.rng) {
+            self.start_outbreak(sink)?;
+        }
+
+        self.vaccinate();
+
+        let infectious = self.people.values().filter(|p| p.is_infectious()).count();
+        let healthcare_funding = self.get_healthcare_funding();
+        let rule = &self.rules.quarantine_rule;
+        let epidemic = &mut self.epidemic;
+        epidemic.quarantined = rule.enabled && epidemic.infected_count >= rule.minimum_infected;
+
+        let mut transmission = TRANSMISSION_RATE * infectious as f32
+            / self.people.len().max(1) as f32
+            / healthcare_funding;
+        if epidemic.quarantined {
+            transmission *= 1. - QUARANTINE_REDUCTION;
+        }
+
+        for per in self.people.values_mut() {
+            per.progress_infection(&mut self.healthcare, &self.rules, &mut self.rng);
+
+            let infection_chance = 1. - (-transmission * per.get_susceptibility()).exp();
+            if per.infection == Infection::Susceptible
+                && self.rng.gen_bool(infection_chance.clamp(0., 1.) as f64)
+            {
+                per.infect();
+                epidemic.infections_last_month += 1;
+            }
+
+            per.welfare_machine
+                .remove_welfare_if(WELFARE_IMPACT_THREE, day, per.is_infected());
+            per.welfare_machine
+                .remove_welfare_if(WELFARE_IMPACT_TWO, day, epidemic.quarantined);
+        }
+
+        epidemic.infected_count = self.people.values().filter(|p| p.is_infected()).count() as i32;
+        epidemic.immune_count = self
+            .people
+            .values()
+            .filter(|p| matches!(p.infection, Infection::Immune { .. }))
+            .count() as i32;
+
+        Ok(())
+    }
+
+    /// Healthcare budget per person compared with what keeps the disease spreading as usual
+    fn get_healthcare_funding(&self) -> f32 {
+        if self.people.is_empty() {
+            return 1.;
+        }
+
+        let budget_per_person = self.healthcare.budget as f32 / self.people.len() as f32;
+        (budget_per_person / EXPECTED_HEALTHCARE_SPENDING_PER_PERSON)
+            .clamp(MINIMUM_HEALTHCARE_FUNDING, MAXIMUM_HEALTHCARE_FUNDING)
+    }
+
+    fn start_outbreak(&mut self, sink: &dyn GameEventSink) -> IncResult<()> {
+        let susceptible: Vec<Uuid> = self
+            .people
+            .values()
+            .filter(|p| p.infection == Infection::Susceptible)
+            .map(|p| p.id)
+            .collect();
+        if susceptible.is_empty() {
+            return Ok(());
+        }
+
+        let mut infected = 0;
+        for _ in 0..OUTBREAK_SIZE {
+            let id = susceptible[self.rng.gen_range(0..susceptible.len())];
+            if let Some(per) = self.people.get_mut(&id) {
+                infected += i32::from(per.infect());
+            }
+        }
+        self.epidemic.infected_count += infected;
+        self.epidemic.infections_last_month += infected;
+
+        sink.emit(GameEvent::OutbreakStarted { infected })
+    }
+
+    /// Spends a day's share of the vaccination budget, starting with the eldest who are not
+    /// already immune
+    fn vaccinate(&mut self) {
+        let doses = (self.epidemic.vaccination_budget / VACCINE_COST / 30) as usize;
+        if doses == 0 {
+            return;
+        }
+
+        let mut susceptible: Vec<(Uuid, i32)> = self
+            .people
+            .values()
+            .filter(|p| p.infection == Infection::Susceptible)
+            .map(|p| (p.id, p.age))
+            .collect();
+        susceptible.sort_by_key(|(_, age)| Reverse(*age));
+
+        for (id, _) in susceptible.into_iter().take(doses) {
+            if let Some(per) = self.people.get_mut(&id) {
+                per.vaccinate();
+                self.epidemic.vaccinations_last_month += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::person::{disease::INFECTIOUS_DAYS, person::Person},
+        game::{sink::RecordingEventSink, structs::QuarantineRule},
+    };
+
+    fn population(size: usize) -> GameState {
+        let mut state = GameState::default();
+        for _ in 0..size {
+            let per = Person {
+                id: Uuid::new_v4(),
+                age: 30,
+                health_percentage: 100,
+                maximum_health: 100,
+                ..Person::default()
+            };
+            state.people.insert(per.id, per);
+        }
+
+        state
+    }
+
+    #[test]
+    fn infected_people_recover_with_immunity() {
+        let mut state = population(1);
+        let id = *state.people.keys().next().unwrap();
+        state.people.get_mut(&id).unwrap().infect();
+        state.epidemic.infected_count = 1;
+
+        let sink = RecordingEventSink::default();
+        for day in 1..=INFECTIOUS_DAYS {
+            state.spread_disease(day, &sink).unwrap();
+        }
+
+        assert!(matches!(
+            state.people[&id].infection,
+            Infection::Immune { .. }
+        ));
+        assert_eq!(state.epidemic.infected_count, 0);
+        assert_eq!(state.epidemic.immune_count, 1);
+    }
+
+    #[test]
+    fn vaccines_go_to_the_eldest_first() {
+        let mut state = population(3);
+        let eldest = *state.people.keys().next().unwrap();
+        state.people.get_mut(&eldest).unwrap().age = 80;
+        state.epidemic.vaccination_budget = VACCINE_COST * 30;
+
+        state.vaccinate();
+
+        assert!(matches!(
+            state.people[&eldest].infection,
+            Infection::Immune { .. }
+        ));
+        assert_eq!(state.epidemic.vaccinations_last_month, 1);
+    }
+
+    #[test]
+    fn well_funded_healthcare_slows_the_spread() {
+        let infections = |budget_per_person: i64| {
+            let mut state = population(2000);
+            state.healthcare.budget = budget_per_person * 2000;
+            for per in state.people.values_mut().take(200) {
+                per.infect();
+            }
+
+            state
+                .spread_disease(1, &RecordingEventSink::default())
+                .unwrap();
+            state.epidemic.infections_last_month
+        };
+
+        let underfunded = infections(0);
+        let funded = infections(4000);
+        let well_funded = infections(100000);
+        assert!(underfunded > funded);
+        assert!(funded > well_funded);
+    }
+
+    #[test]
+    fn healthcare_funding_is_kept_within_limits() {
+        let mut state = population(10);
+        assert_eq!(state.get_healthcare_funding(), MINIMUM_HEALTHCARE_FUNDING);
+
+        state.healthcare.budget = 48000;
+        assert_eq!(state.get_healthcare_funding(), 1.2);
+
+        state.healthcare.budget = 1000000;
+        assert_eq!(state.get_healthcare_funding(), MAXIMUM_HEALTHCARE_FUNDING);
+    }
+
+    #[test]
+    fn quarantine_starts_once_enough_people_are_infected() {
+        let mut state = population(10);
+        state.rules.quarantine_rule = QuarantineRule {
+            enabled: true,
+            minimum_infected: 2,
+        };
+        for per in state.people.values_mut().take(2) {
+            per.infect();
+        }
+        state.epidemic.infected_count = 2;
+
+        state
+            .spread_disease(1, &RecordingEventSink::default())
+            .unwrap();
+
+        assert!(state.epidemic.quarantined);
+    }
+}
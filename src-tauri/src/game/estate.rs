@@ -0,0 +1,176 @@
+use uuid::Uuid;
+
+use super::structs::{GameState, InheritanceTaxRule};
+use crate::{
+    common::errors::{Error, IncResult},
+    entities::person::person::Job,
+};
+
+fn get_inheritance_tax(rule: &InheritanceTaxRule, estate: f64) -> f64 {
+    if !rule.enabled {
+        return 0.;
+    }
+
+    (estate - rule.minimum_estate as f64).max(0.) * rule.tax_rate as f64
+}
+
+impl GameState {
+    /// Settles the estate of a person who has died, before they are removed from the game.
+    /// Their business is passed on or closed, debts are paid from what they leave and the rest
+    /// is taxed and passed to their partner or children. Anything without an heir goes to the
+    /// government
+    pub fn settle_estate(&mut self, id: Uuid, dying: &[Uuid]) -> IncResult<()> {
+        let per = self.people.get(&id).ok_or(Error::DangerUnexpected)?;
+
+        // A surviving partner inherits everything, otherwise it is split between the children
+        let heirs: Vec<Uuid> = per
+            .partner_id
+            .filter(|partner_id| !dying.contains(partner_id))
+            .map(|partner_id| vec![partner_id])
+            .unwrap_or_else(|| per.child_ids.clone())
+            .into_iter()
+            .filter(|heir_id| self.people.contains_key(heir_id) && !dying.contains(heir_id))
+            .collect();
+        let job = per.job.clone();
+        let debts: f64 = per.debts.iter().map(|debt| debt.owed as f64).sum();
+        let mut estate = per.balance.max(0.) as f64;
+
+        if let Job::BusinessOwner(bid) = job {
+            estate += self.pass_on_business(bid, &heirs);
+        }
+
+        // Debts the estate can not cover are written off
+        estate -= debts.min(estate);
+
+        let tax = get_inheritance_tax(&self.rules.inheritance_tax_rule, estate);
+        self.government_balance += tax as i64;
+        estate -= tax;
+
+        if heirs.is_empty() {
+            self.government_balance += estate as i64;
+            return Ok(());
+        }
+
+        let share = estate / heirs.len() as f64;
+        for heir_id in heirs {
+            let heir = self
+                .people
+                .get_mut(&heir_id)
+                .ok_or(Error::DangerUnexpected)?;
+            heir.balance += share as f32;
+        }
+
+        Ok(())
+    }
+
+    /// Gives the business to the oldest adult heir who does not already own one, otherwise it is
+    /// closed.
+    /// Returns what was left in a closed business' balance, which becomes part of the estate
+    fn pass_on_business(&mut self, bid: Uuid, heirs: &[Uuid]) -> f64 {
+        let heir = heirs
+            .iter()
+            .filter_map(|heir_id| self.people.get(heir_id))
+            .filter(|heir| heir.age >= 18 && !matches!(heir.job, Job::BusinessOwner(_)))
+            .max_by_key(|heir| heir.age)
+            .cloned();
+
+        if let Some(heir) = heir {
+            if !self.businesses.contains_key(&bid) {
+                return 0.;
+            }
+
+            let heir_id = heir.id;
+            self.resign_if_employed(heir);
+
+            if let Some(heir) = self.people.get_mut(&heir_id) {
+                heir.job = Job::BusinessOwner(bid);
+            }
+            if let Some(business) = self.businesses.get_mut(&bid) {
+                business.owner_id = heir_id;
+            }
+
+            return 0.;
+        }
+
+        let business = match self.close_business(bid) {
+            Some(business) => business,
+            None => return 0.,
+        };
+
+        business.balance.max(0.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{business::Business, person::person::Person};
+
+    fn person(age: i32, balance: f32) -> Person {
+        Person {
+            id: Uuid::new_v4(),
+            age,
+            balance,
+            ..Person::default()
+        }
+    }
+
+    #[test]
+    fn estate_is_taxed_and_split_between_children() {
+        let mut state = GameState::default();
+        state.rules.inheritance_tax_rule = InheritanceTaxRule {
+            enabled: true,
+            minimum_estate: 1000,
+            tax_rate: 0.5,
+        };
+
+        let children = [person(30, 0.), person(25, 0.)];
+        let mut parent = person(80, 5000.);
+        parent.child_ids = children.iter().map(|child| child.id).collect();
+
+        let government_balance = state.government_balance;
+        let parent_id = parent.id;
+        state.people.insert(parent.id, parent);
+        for child in children.iter() {
+            state.people.insert(child.id, child.clone());
+        }
+
+        state.settle_estate(parent_id, &[parent_id]).unwrap();
+
+        assert_eq!(state.government_balance - government_balance, 2000);
+        for child in children.iter() {
+            assert_eq!(state.people[&child.id].balance, 1500.);
+        }
+    }
+
+    #[test]
+    fn business_passes_to_an_adult_heir_or_closes() {
+        let mut state = GameState::default();
+
+        let child = person(40, 0.);
+        let mut owner = person(70, 0.);
+        let business = Business {
+            id: Uuid::new_v4(),
+            owner_id: owner.id,
+            balance: 800.,
+            ..Business::default()
+        };
+        owner.job = Job::BusinessOwner(business.id);
+        owner.child_ids.push(child.id);
+
+        let (owner_id, child_id, bid) = (owner.id, child.id, business.id);
+        state.businesses.insert(bid, business);
+        state.people.insert(owner_id, owner);
+        state.people.insert(child_id, child);
+
+        state.settle_estate(owner_id, &[owner_id]).unwrap();
+        assert_eq!(state.businesses[&bid].owner_id, child_id);
+        assert!(state.people[&child_id].job == Job::BusinessOwner(bid));
+
+        // With no heir left the business is closed and its balance goes to the government
+        let government_balance = state.government_balance;
+        state.settle_estate(child_id, &[child_id]).unwrap();
+        assert!(state.businesses.is_empty());
+        assert_eq!(state.government_balance - government_balance, 800);
+    }
+}
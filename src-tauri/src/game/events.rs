@@ -1,20 +1,24 @@
-use std::{ops::{Add, AddAssign}, default};
-
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "desktop")]
 use serde_json::json;
-use tauri::{AppHandle, Manager, State};
+#[cfg(feature = "desktop")]
+use tauri::State;
 
-use crate::{
-    common::{errors::{Error, IncResult}, util::SlotArray},
-    entities::{business::Business, person::person::Person},
+use crate::common::{
+    errors::{Error, IncResult},
+    util::SlotArray,
 };
+#[cfg(feature = "desktop")]
+use crate::entities::business::Business;
 
+#[cfg(feature = "desktop")]
+use super::{rules::RuleInfo, state_manager::GameStateSafe, structs::TaxBracket};
 use super::{
-    state_manager::GameStateSafe,
-    structs::{GameState, HealthcareGroup},
+    sink::{GameEvent, GameEventSink},
+    structs::{HealthcareGroup, HouseholdDebtState, IncomeTaxState, PriceState, PublicDebtState},
 };
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum App {
     Finance = 1,
     Healthcare = 2,
@@ -46,7 +50,7 @@ impl Default for MonthlyGraphData {
             three_months: vec![-1; 90],
             six_months: vec![-1; 180],
             one_year: vec![-1; 12],
-            three_years: vec![-1; 36]
+            three_years: vec![-1; 36],
         }
     }
 }
@@ -59,7 +63,7 @@ impl Default for DailyGraphData {
             three_months: vec![-1; 90],
             six_months: vec![-1; 180],
             one_year: vec![-1; 12],
-            three_years: vec![-1; 36]
+            three_years: vec![-1; 36],
         }
     }
 }
@@ -70,11 +74,13 @@ pub struct FinanceAppOpenedPayload {
     pub average_monthly_income: i32,
     pub expected_person_income: i64,
     pub expected_business_income: i64,
+    pub sales_tax_last_month: i64,
     pub used_hospital_capacity: i32,
     pub total_hospital_capacity: i32,
     pub spare_hospital_capacity: i32,
     pub business_tax_rate: i32,
     pub tax_rate: i32,
+    pub income_tax: IncomeTaxState,
     pub healthcare_budget: i64,
     pub used_welfare_budget: i64,
     pub welfare_budget: i64,
@@ -90,6 +96,12 @@ pub struct FinanceAppOpenedPayload {
     pub government_balance_prediction_graph_data: MonthlyGraphData,
     pub average_monthly_income_graph_data: MonthlyGraphData,
     pub government_losses_graph_data: MonthlyGraphData,
+    pub household_debt: HouseholdDebtState,
+    pub household_debt_graph_data: MonthlyGraphData,
+    pub public_debt: PublicDebtState,
+    pub public_debt_graph_data: MonthlyGraphData,
+    pub prices: PriceState,
+    pub inflation_graph_data: MonthlyGraphData,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -104,6 +116,14 @@ pub struct HealthcareAppOpenedPayload {
     pub child_care: HealthcareGroup,
     pub adult_care: HealthcareGroup,
     pub elder_care: HealthcareGroup,
+    pub charges_paid_last_month: i64,
+    pub charges_unpaid_last_month: i64,
+    pub vaccination_budget: i64,
+    pub infected_count: i32,
+    pub immune_count: i32,
+    pub quarantined: bool,
+    pub infections_last_month: i32,
+    pub vaccinations_last_month: i32,
     pub rules: serde_json::Value,
 
     pub population_graph_data: DailyGraphData,
@@ -111,6 +131,7 @@ pub struct HealthcareAppOpenedPayload {
     pub deaths_graph_data: DailyGraphData,
     pub life_expectancy_graph_data: DailyGraphData,
     pub hospital_usage_capacity_graph_data: DailyGraphData,
+    pub infected_graph_data: DailyGraphData,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -118,6 +139,20 @@ pub struct WelfareAppOpenedPayload {
     pub average_welfare: i32,
     pub average_unemployed_welfare: i32,
     pub unemployed_count: i32,
+    pub homeless_count: i32,
+    pub market_rent: i32,
+    pub vacant_homes: i32,
+    pub vacancies: i32,
+    pub hires_last_month: i32,
+    pub quits_last_month: i32,
+    pub job_switches_last_month: i32,
+    pub minimum_wage_layoffs_last_month: i32,
+    pub education_budget: i64,
+    pub students: i32,
+    pub graduates_last_month: i32,
+    pub pension_budget: i64,
+    pub pensions_paid_last_month: i64,
+    pub retiree_count: i32,
     pub rules: serde_json::Value,
 
     pub unemployed_count_graph_data: MonthlyGraphData,
@@ -138,23 +173,34 @@ pub struct BusinessAppOpenedPayload {
 }
 
 pub fn get_monthly_data(data: &SlotArray<i64>, get_total: bool) -> MonthlyGraphData {
-    let mut monthly_graph_data = MonthlyGraphData::default();
+    let mut monthly_graph_data = MonthlyGraphData {
+        three_months: get_last_days(90, data),
+        six_months: get_last_days(180, data),
+        ..MonthlyGraphData::default()
+    };
 
-    monthly_graph_data.three_months = get_last_days(90, &data);
-    monthly_graph_data.six_months = get_last_days(180, &data);
     construct_months_from_day_array(data, &mut monthly_graph_data.one_year, 12, get_total);
     construct_months_from_day_array(data, &mut monthly_graph_data.three_years, 36, get_total);
 
     monthly_graph_data
 }
 
-pub fn construct_months_from_day_array(source_array: &SlotArray<i64>, dest_array: &mut Vec<i64>, months: u16, get_total: bool) {
+pub fn construct_months_from_day_array(
+    source_array: &SlotArray<i64>,
+    dest_array: &mut Vec<i64>,
+    months: u16,
+    get_total: bool,
+) {
     let mut end_idx = (source_array.current_idx - 1) as isize;
-    if end_idx < 0 { end_idx = 0 }
+    if end_idx < 0 {
+        end_idx = 0
+    }
 
     for i in 0..months {
         let mut start_idx: isize = ((i * 30) as isize) - 1;
-        if start_idx < 0 { start_idx = 0 }
+        if start_idx < 0 {
+            start_idx = 0
+        }
 
         if !get_total {
             dest_array.push(source_array.array[start_idx as usize]);
@@ -175,23 +221,26 @@ pub fn construct_months_from_day_array(source_array: &SlotArray<i64>, dest_array
 
 pub fn get_last_days(days: u16, source_array: &SlotArray<i64>) -> Vec<i64> {
     let mut end_idx = (source_array.current_idx - 1) as isize;
-    if end_idx < 0 { end_idx = 0 }
+    if end_idx < 0 {
+        end_idx = 0
+    }
 
     let mut start_idx = end_idx - days as isize;
-    if start_idx < 0 { start_idx = 0 }
+    if start_idx < 0 {
+        start_idx = 0
+    }
 
     source_array.slice(start_idx as usize, end_idx as usize)
 }
 
 pub fn get_daily_data(data: &SlotArray<i64>) -> DailyGraphData {
-    let mut daily_graph_data = DailyGraphData::default();
-
-    daily_graph_data.one_week = get_last_days(7, &data);
-    daily_graph_data.one_month = get_last_days(30, &data);
-    daily_graph_data.three_months = get_last_days(90, &data);
-    daily_graph_data.six_months = get_last_days(180, &data);
-
-    daily_graph_data
+    DailyGraphData {
+        one_week: get_last_days(7, data),
+        one_month: get_last_days(30, data),
+        three_months: get_last_days(90, data),
+        six_months: get_last_days(180, data),
+        ..DailyGraphData::default()
+    }
 }
 
 pub fn get_app_from_id(app_id: u8) -> Option<App> {
@@ -204,6 +253,7 @@ pub fn get_app_from_id(app_id: u8) -> Option<App> {
     }
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn app_open(state_mux: State<'_, GameStateSafe>, app_id: u8) -> IncResult<String> {
     let mut state = state_mux.lock().unwrap();
@@ -220,10 +270,12 @@ pub fn app_open(state_mux: State<'_, GameStateSafe>, app_id: u8) -> IncResult<St
                 average_monthly_income: state.finance_data.average_monthly_income,
                 expected_person_income: state.finance_data.expected_person_income,
                 expected_business_income: state.finance_data.expected_business_income,
+                sales_tax_last_month: state.finance_data.sales_tax_last_month,
                 used_hospital_capacity: state.healthcare.get_current_capacity(),
                 total_hospital_capacity: state.healthcare.total_capacity,
                 business_tax_rate: (state.business_tax_rate * 100.) as i32,
                 tax_rate: (state.tax_rate * 100.) as i32,
+                income_tax: state.income_tax.clone(),
                 healthcare_budget: state.healthcare.budget,
                 welfare_budget: state.welfare_budget,
                 business_budget: state.business_budget,
@@ -234,7 +286,8 @@ pub fn app_open(state_mux: State<'_, GameStateSafe>, app_id: u8) -> IncResult<St
                     * state.rules.business_funding_rule.business_count as i64),
                 used_welfare_budget: ((state.rules.cover_food_rule.people_count * 4)
                     + (state.rules.cover_food_unemployed_rule.people_count * 4))
-                    as i64,
+                    as i64
+                    + state.rules.housing_subsidy_rule.budget_cost,
                 spare_hospital_capacity: (state.healthcare.total_capacity
                     - (state.healthcare.childcare.total_capacity
                         + state.healthcare.adultcare.total_capacity
@@ -243,12 +296,34 @@ pub fn app_open(state_mux: State<'_, GameStateSafe>, app_id: u8) -> IncResult<St
                 rules: json!({
                     "tax": state.rules.tax_rule,
                     "business_tax": state.rules.business_tax_rule,
+                    "sales_tax": state.rules.sales_tax_rule,
                 }),
 
-                government_balance_graph_data: get_monthly_data(&state.government_balance_graph_data, false),
-                government_balance_prediction_graph_data: get_monthly_data(&state.government_balance_prediction_graph_data, false),
-                average_monthly_income_graph_data: get_monthly_data(&state.average_monthly_income_graph_data, false),
-                government_losses_graph_data: get_monthly_data(&state.government_losses_graph_data, false),
+                government_balance_graph_data: get_monthly_data(
+                    &state.government_balance_graph_data,
+                    false,
+                ),
+                government_balance_prediction_graph_data: get_monthly_data(
+                    &state.government_balance_prediction_graph_data,
+                    false,
+                ),
+                average_monthly_income_graph_data: get_monthly_data(
+                    &state.average_monthly_income_graph_data,
+                    false,
+                ),
+                government_losses_graph_data: get_monthly_data(
+                    &state.government_losses_graph_data,
+                    false,
+                ),
+                household_debt: state.household_debt.clone(),
+                household_debt_graph_data: get_monthly_data(
+                    &state.household_debt_graph_data,
+                    false,
+                ),
+                public_debt: state.public_debt.clone(),
+                public_debt_graph_data: get_monthly_data(&state.public_debt_graph_data, false),
+                prices: state.prices.clone(),
+                inflation_graph_data: get_monthly_data(&state.inflation_graph_data, false),
             };
 
             serde_json::to_string(&payload)
@@ -266,16 +341,29 @@ pub fn app_open(state_mux: State<'_, GameStateSafe>, app_id: u8) -> IncResult<St
                 child_care: state.healthcare.childcare,
                 adult_care: state.healthcare.adultcare,
                 elder_care: state.healthcare.eldercare,
+                charges_paid_last_month: state.healthcare.charges_paid_last_month,
+                charges_unpaid_last_month: state.healthcare.charges_unpaid_last_month,
+                vaccination_budget: state.epidemic.vaccination_budget,
+                infected_count: state.epidemic.infected_count,
+                immune_count: state.epidemic.immune_count,
+                quarantined: state.epidemic.quarantined,
+                infections_last_month: state.epidemic.infections_last_month,
+                vaccinations_last_month: state.epidemic.vaccinations_last_month,
                 rules: json!({
                     "deny_past_age": state.rules.deny_age_rule,
-                    "deny_past_health": state.rules.deny_health_percentage_rule
+                    "deny_past_health": state.rules.deny_health_percentage_rule,
+                    "hospital_charges": state.rules.hospital_charges_rule,
+                    "quarantine": state.rules.quarantine_rule,
                 }),
 
                 population_graph_data: get_daily_data(&state.population_graph_data),
                 births_graph_data: get_daily_data(&state.births_graph_data),
                 deaths_graph_data: get_daily_data(&state.deaths_graph_data),
                 life_expectancy_graph_data: get_daily_data(&state.life_expectancy_graph_data),
-                hospital_usage_capacity_graph_data: get_daily_data(&state.hospital_usage_capacity_graph_data),
+                hospital_usage_capacity_graph_data: get_daily_data(
+                    &state.hospital_usage_capacity_graph_data,
+                ),
+                infected_graph_data: get_daily_data(&state.infected_graph_data),
             };
 
             serde_json::to_string(&payload)
@@ -286,14 +374,39 @@ pub fn app_open(state_mux: State<'_, GameStateSafe>, app_id: u8) -> IncResult<St
                 average_welfare: state.average_welfare,
                 average_unemployed_welfare: state.average_welfare_unemployed,
                 unemployed_count: state.unemployed_count,
+                homeless_count: state.housing.homeless_count,
+                market_rent: state.housing.market_rent,
+                vacant_homes: state.housing.vacant_homes(),
+                vacancies: state.labour_market.vacancies,
+                hires_last_month: state.labour_market.hires_last_month,
+                quits_last_month: state.labour_market.quits_last_month,
+                job_switches_last_month: state.labour_market.job_switches_last_month,
+                minimum_wage_layoffs_last_month: state
+                    .labour_market
+                    .minimum_wage_layoffs_last_month,
+                education_budget: state.education.budget,
+                students: state.education.students,
+                graduates_last_month: state.education.graduates_last_month,
+                pension_budget: state.pensions.budget,
+                pensions_paid_last_month: state.pensions.paid_last_month,
+                retiree_count: state.pensions.retiree_count,
                 rules: json!({
                     "cover_food": state.rules.cover_food_rule,
                     "cover_food_unemployed": state.rules.cover_food_unemployed_rule,
+                    "rent_cap": state.rules.rent_cap_rule,
+                    "housing_subsidy": state.rules.housing_subsidy_rule,
+                    "tuition": state.rules.tuition_rule,
+                    "pension": state.rules.pension_rule,
                 }),
 
-                unemployed_count_graph_data: get_monthly_data(&state.unemployed_count_graph_data, false),
+                unemployed_count_graph_data: get_monthly_data(
+                    &state.unemployed_count_graph_data,
+                    false,
+                ),
                 average_welfare_graph_data: get_daily_data(&state.average_welfare_graph_data),
-                average_unemployed_welfare_graph_data: get_daily_data(&state.average_unemployed_welfare_graph_data),
+                average_unemployed_welfare_graph_data: get_daily_data(
+                    &state.average_unemployed_welfare_graph_data,
+                ),
             };
 
             serde_json::to_string(&payload)
@@ -306,15 +419,25 @@ pub fn app_open(state_mux: State<'_, GameStateSafe>, app_id: u8) -> IncResult<St
                 average_monthly_income: state.business_data.average_monthly_income,
                 rules: json!({
                     "funding": state.rules.business_funding_rule,
+                    "minimum_wage": state.rules.minimum_wage_rule,
                 }),
 
-                business_count_graph_data: get_monthly_data(&state.business_count_graph_data, false),
-                average_employees_graph_data: get_monthly_data(&state.average_employees_graph_data, false),
-                average_monthly_income_graph_data: get_monthly_data(&state.average_monthly_income_graph_data, false),
+                business_count_graph_data: get_monthly_data(
+                    &state.business_count_graph_data,
+                    false,
+                ),
+                average_employees_graph_data: get_monthly_data(
+                    &state.average_employees_graph_data,
+                    false,
+                ),
+                average_monthly_income_graph_data: get_monthly_data(
+                    &state.average_monthly_income_graph_data,
+                    false,
+                ),
             };
 
             serde_json::to_string(&payload)
-        },
+        }
 
         _ => Ok(String::new()),
     }?;
@@ -323,6 +446,7 @@ pub fn app_open(state_mux: State<'_, GameStateSafe>, app_id: u8) -> IncResult<St
     Ok(ret)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn app_close(state_mux: State<'_, GameStateSafe>, app_id: u8) {
     let mut state = state_mux.lock().unwrap();
@@ -334,43 +458,25 @@ pub fn app_close(state_mux: State<'_, GameStateSafe>, app_id: u8) {
     *state.open_apps.entry(app).or_insert(false) = false;
 }
 
-fn set_rule(state: &mut GameState, id: i32, enabled: bool) {
-    match id {
-        0 => {
-            state.rules.tax_rule.enabled = enabled;
-        }
-        1 => {
-            state.rules.business_tax_rule.enabled = enabled;
-        }
-        2 => {
-            state.rules.business_funding_rule.enabled = enabled;
-        }
-        3 => {
-            state.rules.deny_age_rule.enabled = enabled;
-        }
-        4 => {
-            state.rules.deny_health_percentage_rule.enabled = enabled;
-        }
-        5 => {
-            state.rules.cover_food_rule.enabled = enabled;
-        }
-        6 => {
-            state.rules.cover_food_unemployed_rule.enabled = enabled;
-        }
-        _ => unreachable!(),
-    };
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn list_rules(state_mux: State<'_, GameStateSafe>) -> IncResult<Vec<RuleInfo>> {
+    let state = state_mux.lock().unwrap();
+    state.list_rules()
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn enable_rule(state_mux: State<'_, GameStateSafe>, rule_id: i32) {
+pub fn enable_rule(state_mux: State<'_, GameStateSafe>, rule_id: i32) -> IncResult<()> {
     let mut state = state_mux.lock().unwrap();
-    set_rule(&mut state, rule_id, true);
+    state.set_rule_enabled(rule_id, true)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn disable_rule(state_mux: State<'_, GameStateSafe>, rule_id: i32) {
+pub fn disable_rule(state_mux: State<'_, GameStateSafe>, rule_id: i32) -> IncResult<()> {
     let mut state = state_mux.lock().unwrap();
-    set_rule(&mut state, rule_id, false);
+    state.set_rule_enabled(rule_id, false)
 }
 
 pub fn json_get_f64(json: &serde_json::Value, key: &str) -> IncResult<f64> {
@@ -389,6 +495,7 @@ pub fn json_get_i64(json: &serde_json::Value, key: &str) -> IncResult<i64> {
         .ok_or_else(|| Error::Danger(format!("Failed to convert '{}' to i64.", val)))
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_rule(
     state_mux: State<'_, GameStateSafe>,
@@ -396,90 +503,11 @@ pub fn update_rule(
     data: serde_json::Value,
 ) -> IncResult<serde_json::Value> {
     let mut state = state_mux.lock().unwrap();
-    match rule_id {
-        0 => {
-            state.rules.tax_rule.minimum_salary = json_get_i64(&data, "minimum_salary")? as i32;
-            state.rules.tax_rule.tax_rate = json_get_f64(&data, "tax_rate")? as f32;
-        }
-        1 => {
-            state.rules.business_tax_rule.minimum_monthly_income =
-                json_get_f64(&data, "minimum_monthly_income")?;
-            state.rules.business_tax_rule.tax_rate = json_get_f64(&data, "tax_rate")? as f32;
-        }
-        2 => {
-            let fund = json_get_i64(&data, "fund")?;
-            let maximum_income = json_get_i64(&data, "maximum_income")?;
-            let business_count = json_get_i64(&data, "business_count")? as i32;
-
-            let budget_cost = fund * business_count as i64;
-            if budget_cost > state.business_budget {
-                return Err(Error::Danger(
-                    "This fund exceeds the budget for businesses".to_string(),
-                ));
-            }
-
-            state.rules.business_funding_rule.fund = fund;
-            state.rules.business_funding_rule.maximum_income = maximum_income;
-            state.rules.business_funding_rule.business_count = business_count;
-            state.rules.business_funding_rule.budget_cost = budget_cost;
-
-            return Ok(json!({
-                "budget_cost": budget_cost,
-            }));
-        }
-        3 => {
-            state.rules.deny_age_rule.maximum_age = json_get_i64(&data, "maximum_age")? as i32;
-        }
-        4 => {
-            state.rules.deny_health_percentage_rule.maximum_percentage =
-                json_get_i64(&data, "maximum_percentage")? as i32;
-        }
-        5 => {
-            let people_count = json_get_i64(&data, "people_count")? as i32;
-            let maximum_salary = json_get_i64(&data, "maximum_salary")? as i32;
-            let budget_cost = people_count as i64 * 4;
-
-            let remaining_budget =
-                state.welfare_budget - state.rules.cover_food_unemployed_rule.budget_cost;
-            if budget_cost > remaining_budget {
-                return Err(Error::Danger(
-                    "Cannot cover food as the cost exceeds the welfare budget.".to_string(),
-                ));
-            }
-
-            state.rules.cover_food_rule.people_count = people_count;
-            state.rules.cover_food_rule.maximum_salary = maximum_salary;
-            state.rules.cover_food_rule.budget_cost = budget_cost;
-
-            return Ok(json!({
-                "budget_cost": budget_cost,
-            }));
-        }
-        6 => {
-            let people_count = json_get_i64(&data, "people_count")? as i32;
-            let budget_cost = people_count as i64 * 4;
-
-            let remaining_budget = state.welfare_budget - state.rules.cover_food_rule.budget_cost;
-            if budget_cost > remaining_budget {
-                return Err(Error::Danger(
-                    "Cannot cover food as the cost exceeds the welfare budget.".to_string(),
-                ));
-            }
-
-            state.rules.cover_food_unemployed_rule.people_count = people_count;
-            state.rules.cover_food_unemployed_rule.budget_cost = budget_cost;
-
-            return Ok(json!({
-                "budget_cost": budget_cost,
-            }));
-        }
-        _ => unreachable!(),
-    };
-
-    Ok(json!({}))
+    state.update_rule(rule_id, &data)
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AppUpdateType {
     Day,
     Month,
@@ -488,44 +516,39 @@ pub enum AppUpdateType {
 pub fn update_app(
     app: App,
     payload: serde_json::Value,
-    app_handle: &AppHandle,
+    sink: &dyn GameEventSink,
     update_type: AppUpdateType,
-) {
-    let app_id = app as u8;
-
-    let update_type_str;
-
-    if update_type == AppUpdateType::Day {
-        update_type_str = String::from("day");
-    } else {
-        update_type_str = String::from("month");
-    }
-
-    app_handle
-        .emit_all(
-            "update_app",
-            json!({ "app_id": app_id, "data": payload, "update_type": update_type_str }),
-        )
-        .unwrap();
+) -> IncResult<()> {
+    sink.emit(GameEvent::AppUpdate {
+        app,
+        update_type,
+        data: payload,
+    })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_tax_rate(state_mux: State<'_, GameStateSafe>, tax_rate: i32) -> i64 {
     let mut state = state_mux.lock().unwrap();
 
     state.tax_rate = tax_rate as f32 / 100.;
 
-    let mut total_income: i64 = 0;
-
-    for per in state.people.values() {
-        let tax_rate = Person::get_tax_rate(&state.rules.tax_rule, state.tax_rate, per.salary);
-        total_income += ((per.salary as f32 / 12.) * tax_rate) as i64;
-    }
-
-    state.finance_data.expected_person_income = total_income;
+    state.finance_data.expected_person_income = state.get_expected_person_income();
     state.finance_data.expected_person_income
 }
 
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn update_income_tax(
+    state_mux: State<'_, GameStateSafe>,
+    personal_allowance: i32,
+    brackets: Vec<TaxBracket>,
+) -> IncResult<i64> {
+    let mut state = state_mux.lock().unwrap();
+    state.update_income_tax(personal_allowance, brackets)
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_business_tax_rate(state_mux: State<'_, GameStateSafe>, tax_rate: i32) -> i64 {
     let mut state = state_mux.lock().unwrap();
@@ -551,6 +574,7 @@ pub fn update_business_tax_rate(state_mux: State<'_, GameStateSafe>, tax_rate: i
     state.finance_data.expected_business_income
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_healthcare_budget(
     state_mux: State<'_, GameStateSafe>,
@@ -592,6 +616,7 @@ pub fn update_healthcare_budget(
     })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_welfare_budget(
     state_mux: State<'_, GameStateSafe>,
@@ -616,6 +641,7 @@ pub fn update_welfare_budget(
     json!({})
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_business_budget(
     state_mux: State<'_, GameStateSafe>,
@@ -640,6 +666,105 @@ pub fn update_business_budget(
     json!({})
 }
 
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn update_education_budget(
+    state_mux: State<'_, GameStateSafe>,
+    new_budget: i64,
+) -> serde_json::Value {
+    let mut state = state_mux.lock().unwrap();
+
+    let old_budget = state.education.budget;
+
+    state.education.budget = new_budget;
+    let spare_budget = state.get_spare_budget();
+
+    if new_budget > old_budget && spare_budget <= 0 {
+        state.education.budget = old_budget;
+        return json!({
+            "error": "Cannot afford this budget",
+        });
+    }
+
+    state.spare_budget = spare_budget;
+
+    json!({})
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn update_pension_budget(
+    state_mux: State<'_, GameStateSafe>,
+    new_budget: i64,
+) -> serde_json::Value {
+    let mut state = state_mux.lock().unwrap();
+
+    let old_budget = state.pensions.budget;
+
+    state.pensions.budget = new_budget;
+    let spare_budget = state.get_spare_budget();
+
+    if new_budget > old_budget && spare_budget <= 0 {
+        state.pensions.budget = old_budget;
+        return json!({
+            "error": "Cannot afford this budget",
+        });
+    }
+
+    state.spare_budget = spare_budget;
+
+    json!({})
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn update_vaccination_budget(
+    state_mux: State<'_, GameStateSafe>,
+    new_budget: i64,
+) -> serde_json::Value {
+    let mut state = state_mux.lock().unwrap();
+
+    let old_budget = state.epidemic.vaccination_budget;
+
+    state.epidemic.vaccination_budget = new_budget;
+    let spare_budget = state.get_spare_budget();
+
+    if new_budget > old_budget && spare_budget <= 0 {
+        state.epidemic.vaccination_budget = old_budget;
+        return json!({
+            "error": "Cannot afford this budget",
+        });
+    }
+
+    state.spare_budget = spare_budget;
+
+    json!({})
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn issue_bonds(state_mux: State<'_, GameStateSafe>, amount: i64) -> IncResult<PublicDebtState> {
+    let mut state = state_mux.lock().unwrap();
+    state.issue_bonds(amount)?;
+    state.spare_budget = state.get_spare_budget();
+
+    Ok(state.public_debt.clone())
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn repay_public_debt(
+    state_mux: State<'_, GameStateSafe>,
+    amount: i64,
+) -> IncResult<PublicDebtState> {
+    let mut state = state_mux.lock().unwrap();
+    state.repay_public_debt(amount)?;
+    state.spare_budget = state.get_spare_budget();
+
+    Ok(state.public_debt.clone())
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_childcare_capacity(
     state_mux: State<'_, GameStateSafe>,
@@ -671,6 +796,7 @@ pub fn update_childcare_capacity(
     json!({})
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_adultcare_capacity(
     state_mux: State<'_, GameStateSafe>,
@@ -701,6 +827,7 @@ pub fn update_adultcare_capacity(
     json!({})
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_eldercare_capacity(
     state_mux: State<'_, GameStateSafe>,
@@ -1,31 +1,45 @@
-use maplit::hashmap;
-use rand::Rng;
-use serde_json::json;
-use std::{collections::HashMap, ops::Range};
-use tauri::{AppHandle, Manager};
+use rand::{Rng, SeedableRng};
+use std::{collections::BTreeMap, ops::Range};
 
 use crate::{
-    common::{config::Config, errors::IncResult, util::percentage_based_output_int},
+    common::{
+        config::{Config, ProductConfig},
+        errors::IncResult,
+        util::{percentage_based_output_int, GameRng},
+    },
     entities::{
         business::{Business, ProductType},
-        person::person::{
-            EducationLevel::{self, *},
-            Job, Person,
+        person::{
+            education::{EXPECTED_SPENDING_PER_PUPIL, SCHOOL_STARTING_AGE},
+            person::{
+                EducationLevel::{self, *},
+                Job, Person,
+            },
         },
     },
 };
 
-use super::{state_manager::GameStateSafe, structs::HealthcareGroup};
-
-pub fn generate_education_level(config: &Config) -> EducationLevel {
-    percentage_based_output_int::<EducationLevel>(hashmap! {
-        NoFormalEducation => (config.no_education.chance),
-        HighSchoolDiploma => (config.high_school_diploma.chance),
-        College => (config.college.chance),
-        AssociateDegree => (config.associate_degree.chance),
-        Bachelors => (config.bachelors.chance),
-        AdvancedDegree => (config.advanced_degree.chance),
-    })
+use super::{
+    sink::{GameEvent, GameEventSink, NoopEventSink},
+    state_manager::GameStateSafe,
+    structs::{GameState, HealthcareGroup, HousingState},
+};
+
+pub const STARTING_MARKET_RENT: i32 = 900;
+const STARTING_HOMES_PER_ADULT: f32 = 1.05; // A few homes are left vacant so that people can move
+
+pub fn generate_education_level(config: &Config, rng: &mut GameRng) -> EducationLevel {
+    percentage_based_output_int::<EducationLevel>(
+        vec![
+            (NoFormalEducation, config.no_education.chance),
+            (HighSchoolDiploma, config.high_school_diploma.chance),
+            (College, config.college.chance),
+            (AssociateDegree, config.associate_degree.chance),
+            (Bachelors, config.bachelors.chance),
+            (AdvancedDegree, config.advanced_degree.chance),
+        ],
+        rng,
+    )
 }
 
 pub fn get_expected_salary_range(config: &Config, education_level: &EducationLevel) -> Range<i32> {
@@ -45,30 +59,40 @@ pub fn get_expected_salary_range(config: &Config, education_level: &EducationLev
     }
 }
 
+pub fn get_product_config(config: &Config, product_type: ProductType) -> &ProductConfig {
+    match product_type {
+        ProductType::Leisure => &config.leisure,
+        ProductType::Food => &config.food,
+        ProductType::Housing => &config.housing,
+        ProductType::Furniture => &config.furniture,
+        ProductType::HealthcareGoods => &config.healthcare_goods,
+    }
+}
+
 pub fn generate_game(
     state_mux: &GameStateSafe,
     config: &Config,
-    app_handle: &AppHandle,
+    sink: &dyn GameEventSink,
+    seed: u64,
 ) -> IncResult<()> {
-    let mut state = state_mux.lock().unwrap();
+    let mut state_guard = state_mux.lock().unwrap();
+    let state = &mut *state_guard;
+
+    state.seed = seed;
+    state.rng = GameRng::seed_from_u64(seed);
 
-    let mut product_demand: HashMap<ProductType, f32> = HashMap::new();
-    product_demand.insert(ProductType::Leisure, 0.);
-
-    app_handle
-        .emit_all(
-            "loading_status",
-            json!({
-                "Generating people": [
-                    "Generating ages",
-                    "Generating educations",
-                    "Generating debts",
-                    "Generating spending behaviour",
-                    "Generating health"
-                ]
-            }),
-        )
-        .unwrap();
+    let mut product_demand: BTreeMap<ProductType, f32> = BTreeMap::new();
+
+    sink.emit(GameEvent::LoadingStatus {
+        stage: "Generating people",
+        steps: &[
+            "Generating ages",
+            "Generating educations",
+            "Generating debts",
+            "Generating spending behaviour",
+            "Generating health",
+        ],
+    })?;
 
     for _ in 0..config.starting_population {
         let person = Person::new_generate(
@@ -76,50 +100,121 @@ pub fn generate_game(
             &mut product_demand,
             state.tax_rate,
             &state.rules.tax_rule,
+            &state.income_tax,
             state.date.clone(),
+            &mut state.rng,
         )?;
         state.people.insert(person.id, person);
     }
 
-    let mut remaning_market_percentage: f32 = 100.;
+    sink.emit(GameEvent::LoadingStatus {
+        stage: "Generating businesses",
+        steps: &[
+            "Generating salaries",
+            "Generating market",
+            "Generating stock & products",
+            "Generating budget ratios",
+            "Generating jobs",
+        ],
+    })?;
+
+    // Every product has its own market, so businesses only compete with others selling the same product.
+    // Rent is only known once people have found homes, so landlords are generated after everyone else
+    for product_type in ProductType::ALL {
+        if product_type != ProductType::Housing {
+            generate_businesses(state, config, product_type, &mut product_demand)?;
+        }
+    }
 
-    app_handle
-        .emit_all(
-            "loading_status",
-            json!({
-                "Generating businesses": [
-                    "Generating salaries",
-                    "Generating market",
-                    "Generating stock & products",
-                    "Generating budget ratios",
-                    "Generating jobs",
-                ]
-            }),
-        )
-        .unwrap();
-
-    let bus_tax_rate = state.business_tax_rate;
+    // This of course cannot be calculated until after the businesses are generated
+    for per in state.people.values_mut() {
+        per.generate_daily_food_spending(&mut state.rng);
+    }
+
+    // Who lives where depends on what they can afford, everyone else lives with someone
+    let adult_count = state.people.values().filter(|p| p.age >= 18).count();
+    state.housing = HousingState {
+        total_homes: (adult_count as f32 * STARTING_HOMES_PER_ADULT) as i32,
+        market_rent: STARTING_MARKET_RENT,
+        ..HousingState::default()
+    };
+
+    let mut total_rent = 0.;
+    let mut paid_up_front = 0.;
+    let mut sales_tax = 0.;
+    for per in state.people.values_mut() {
+        paid_up_front += per.find_housing(
+            &mut state.housing,
+            &state.rules.rent_cap_rule,
+            &state.rules.sales_tax_rule,
+            &mut sales_tax,
+            &mut state.rng,
+        );
+
+        let rent = per.get_monthly_rent() as f32;
+        per.demand.insert(ProductType::Housing, rent);
+        total_rent += rent;
+    }
+
+    state.government_balance += sales_tax as i64;
+
+    product_demand.insert(ProductType::Housing, total_rent);
+    generate_businesses(state, config, ProductType::Housing, &mut product_demand)?;
+
+    // What people paid to move in is part of the housing businesses' starting balance
+    let landlord_count = state
+        .businesses
+        .values()
+        .filter(|b| b.product_type == ProductType::Housing)
+        .count();
+    for landlord in state
+        .businesses
+        .values_mut()
+        .filter(|b| b.product_type == ProductType::Housing)
+    {
+        landlord.balance += paid_up_front as f64 / landlord_count as f64;
+        landlord.last_month_balance = landlord.balance;
+    }
+
+    // Partners who both found a home give one of them up when they move in together
+    state.generate_households()?;
+
+    Ok(())
+}
+
+/// Generates businesses selling the product until its market is covered, each with a newly generated owner
+fn generate_businesses(
+    state: &mut GameState,
+    config: &Config,
+    product_type: ProductType,
+    product_demand: &mut BTreeMap<ProductType, f32>,
+) -> IncResult<()> {
+    let mut remaning_market_percentage: f32 = 100.;
 
     loop {
         let mut business = Business::default();
 
         let sufficient_businesses = business.generate(
             config,
-            ProductType::Leisure,
-            product_demand[&ProductType::Leisure],
+            product_type,
+            product_demand.get(&product_type).copied().unwrap_or(0.),
             &mut remaning_market_percentage,
             &mut state.people,
-            bus_tax_rate,
+            state.business_tax_rate,
+            Business::get_sales_tax_rate(&state.rules.sales_tax_rule, product_type),
+            &mut state.rng,
         );
         let owner = Person {
             job: Job::BusinessOwner(business.id),
-            age: rand::thread_rng().gen_range(20..70),
+            age: state.rng.gen_range(20..70),
             ..Person::new_generate(
                 config,
-                &mut product_demand,
+                product_demand,
                 state.tax_rate,
                 &state.rules.tax_rule,
+                &state.income_tax,
                 state.date.clone(),
+                &mut state.rng,
             )?
         };
         business.owner_id = owner.id;
@@ -128,27 +223,20 @@ pub fn generate_game(
         state.businesses.insert(business.id, business);
 
         if sufficient_businesses {
-            break;
+            return Ok(());
         }
     }
-
-    // This of course cannot be calculated until after the businesses are generated
-    for per in state.people.values_mut() {
-        per.generate_daily_food_spending();
-    }
-
-    Ok(())
 }
 
 /// Runs 1 month of the game to prepare the economy and get all the required values
 pub fn stabilize_game(
     state_mux: &GameStateSafe,
     config: &Config,
-    app_handle: &AppHandle,
+    sink: &dyn GameEventSink,
 ) -> IncResult<()> {
     let mut state = state_mux.lock().unwrap();
     for day in 1..=30 {
-        state.day_pass(day, None, config)?;
+        state.day_pass(day, &NoopEventSink, config)?; // The daily updates are not needed while stabilizing
     }
 
     let government_balance = state.government_balance;
@@ -173,6 +261,64 @@ pub fn stabilize_game(
     healthcare.adultcare = budget;
     healthcare.eldercare = budget;
 
-    state.month_pass(app_handle, config)?;
+    // Schools start out funded as they expect
+    let pupil_count = state
+        .people
+        .values()
+        .filter(|per| (SCHOOL_STARTING_AGE..18).contains(&per.age) || per.studying_for.is_some())
+        .count();
+    state.education.budget = pupil_count as i64 * EXPECTED_SPENDING_PER_PUPIL;
+
+    // Enough to pay every pension in full
+    state.pensions.budget = state
+        .people
+        .values()
+        .map(|per| per.get_pension_payment(&state.rules.pension_rule) as i64)
+        .sum();
+
+    state.month_pass(sink, config)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::load_config;
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    /// Generates a small game and runs it past the end of its first month
+    fn run_seeded_game(seed: u64) -> Value {
+        let mut config = load_config().unwrap();
+        config.starting_population = 200;
+
+        let state_mux: GameStateSafe = Arc::new(Mutex::new(GameState::default()));
+        generate_game(&state_mux, &config, &NoopEventSink, seed).unwrap();
+
+        let mut state = state_mux.lock().unwrap();
+        let mut months_passed = 0;
+        for _ in 0..35 {
+            state.date.new_day();
+
+            let day = state.date.day;
+            state.day_pass(day, &NoopEventSink, &config).unwrap();
+            if state.date.on_new_month {
+                state.month_pass(&NoopEventSink, &config).unwrap();
+                months_passed += 1;
+            }
+        }
+        assert_eq!(months_passed, 1);
+
+        json!({
+            "people": state.people,
+            "businesses": state.businesses,
+            "government_balance": state.government_balance,
+        })
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_game() {
+        assert_eq!(run_seeded_game(7), run_seeded_game(7));
+        assert_ne!(run_seeded_game(7), run_seeded_game(8));
+    }
+}
@@ -0,0 +1,597 @@
+use rand::Rng;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::structs::{GameState, Household};
+use crate::{
+    common::{
+        errors::{Error, IncResult},
+        util::{chance_one_in, generate_id},
+    },
+    entities::person::person::Person,
+};
+
+const CHILD_MONTHLY_COST: f32 = 4. * 30. + 150.; // Food and childcare for each child in a household
+const PARTNER_AGE_GAP: i32 = 5;
+const MONTHLY_PARTNER_CHANCE: i32 = 36; // Single adults find a partner after about 3 years
+const STARTING_PARTNER_PERCENTAGE: f64 = 0.55;
+
+impl GameState {
+    /// Starts a new household with the given people, taking them out of their old ones
+    pub fn create_household(&mut self, member_ids: &[Uuid]) -> Uuid {
+        let id = generate_id(&mut self.rng);
+        self.households.insert(
+            id,
+            Household {
+                id,
+                member_ids: Vec::new(),
+            },
+        );
+
+        for member_id in member_ids {
+            self.join_household(*member_id, id);
+        }
+
+        id
+    }
+
+    pub fn join_household(&mut self, person_id: Uuid, household_id: Uuid) {
+        self.leave_household(person_id);
+
+        if let Some(household) = self.households.get_mut(&household_id) {
+            household.member_ids.push(person_id);
+        }
+        if let Some(per) = self.people.get_mut(&person_id) {
+            per.household_id = Some(household_id);
+        }
+    }
+
+    /// Takes the person out of their household, which is removed once nobody is left in it
+    pub fn leave_household(&mut self, person_id: Uuid) {
+        let household_id = match self.people.get_mut(&person_id) {
+            Some(per) => per.household_id.take(),
+            None => return,
+        };
+
+        let household = match household_id.and_then(|id| self.households.get_mut(&id)) {
+            Some(household) => household,
+            None => return,
+        };
+
+        household
+            .member_ids
+            .retain(|member_id| *member_id != person_id);
+        if household.member_ids.is_empty() {
+            let id = household.id;
+            self.households.remove(&id);
+        }
+    }
+
+    /// Unlinks a person who has died from their household and partner
+    pub fn remove_from_family(&mut self, person_id: Uuid) {
+        self.leave_household(person_id);
+
+        let partner_id = self.people.get(&person_id).and_then(|per| per.partner_id);
+        if let Some(partner) = partner_id.and_then(|id| self.people.get_mut(&id)) {
+            partner.partner_id = None;
+        }
+    }
+
+    /// Links a newborn to their parents and puts them in their mother's household
+    pub fn add_newborn(&mut self, mother_id: Uuid, mut infant: Person) {
+        let (partner_id, household_id) = match self.people.get(&mother_id) {
+            Some(mother) => (mother.partner_id, mother.household_id),
+            None => (None, None),
+        };
+
+        infant.parent_ids = std::iter::once(mother_id).chain(partner_id).collect();
+        for parent_id in &infant.parent_ids {
+            if let Some(parent) = self.people.get_mut(parent_id) {
+                parent.child_ids.push(infant.id);
+            }
+        }
+
+        let infant_id = infant.id;
+        self.people.insert(infant_id, infant);
+
+        match household_id {
+            Some(household_id) => self.join_household(infant_id, household_id),
+            None => {
+                self.create_household(&[infant_id]);
+            }
+        }
+    }
+
+    /// Pairs the two people, one moves into the other's household along with their children.
+    /// Someone with a home stays where they are, the other gives up theirs
+    fn form_partnership(&mut self, first_id: Uuid, second_id: Uuid) -> IncResult<()> {
+        let first = self.people.get(&first_id).ok_or(Error::DangerUnexpected)?;
+        let second = self.people.get(&second_id).ok_or(Error::DangerUnexpected)?;
+
+        let (stayer, mover) = if second.has_home() && !first.has_home() {
+            (second, first)
+        } else {
+            (first, second)
+        };
+
+        let household_id = stayer.household_id;
+        let (stayer_id, mover_id) = (stayer.id, mover.id);
+        let moving_children: Vec<Uuid> = mover
+            .child_ids
+            .iter()
+            .filter(|child_id| {
+                self.people
+                    .get(*child_id)
+                    .is_some_and(|child| child.age < 18 && child.household_id == mover.household_id)
+            })
+            .copied()
+            .collect();
+
+        if let Some(mover) = self.people.get_mut(&mover_id) {
+            mover.leave_home(&mut self.housing);
+            mover.partner_id = Some(stayer_id);
+        }
+        if let Some(stayer) = self.people.get_mut(&stayer_id) {
+            stayer.partner_id = Some(mover_id);
+        }
+
+        let household_id = match household_id {
+            Some(household_id) => household_id,
+            None => self.create_household(&[stayer_id]),
+        };
+
+        for id in std::iter::once(mover_id).chain(moving_children) {
+            self.join_household(id, household_id);
+        }
+
+        Ok(())
+    }
+
+    fn get_singles_by_age(&self, minimum_age: i32, maximum_age: i32) -> BTreeMap<i32, Vec<Uuid>> {
+        let mut singles: BTreeMap<i32, Vec<Uuid>> = BTreeMap::new();
+
+        for per in self.people.values() {
+            if per.partner_id.is_none() && per.age >= minimum_age && per.age <= maximum_age {
+                singles.entry(per.age).or_default().push(per.id);
+            }
+        }
+
+        singles
+    }
+
+    /// Takes a random single from around the given age, if there are any
+    fn take_single_near_age(
+        &mut self,
+        singles: &mut BTreeMap<i32, Vec<Uuid>>,
+        age: i32,
+    ) -> Option<Uuid> {
+        let partner_age = self
+            .rng
+            .gen_range(age - PARTNER_AGE_GAP..=age + PARTNER_AGE_GAP);
+        let candidates = singles.get_mut(&partner_age)?;
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let idx = self.rng.gen_range(0..candidates.len());
+        Some(candidates.swap_remove(idx))
+    }
+
+    /// Single adults sometimes find a partner among the other singles of a similar age
+    pub fn form_partnerships(&mut self) -> IncResult<()> {
+        let mut singles = self.get_singles_by_age(18, 60);
+        let seekers: Vec<(Uuid, i32)> = singles
+            .iter()
+            .flat_map(|(age, ids)| ids.iter().map(move |id| (*id, *age)))
+            .collect();
+
+        for (seeker_id, age) in seekers {
+            if !chance_one_in(MONTHLY_PARTNER_CHANCE, &mut self.rng) {
+                continue;
+            }
+
+            // The seeker may have been taken as someone else's partner already
+            let seeker_single = singles.get_mut(&age).and_then(|ids| {
+                ids.iter()
+                    .position(|id| *id == seeker_id)
+                    .map(|idx| (ids, idx))
+            });
+            match seeker_single {
+                Some((ids, idx)) => {
+                    ids.swap_remove(idx);
+                }
+                None => continue,
+            }
+
+            if let Some(partner_id) = self.take_single_near_age(&mut singles, age) {
+                self.form_partnership(seeker_id, partner_id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gives every adult a household and pairs some of them up.
+    /// Children are placed with parents old enough to have them
+    pub fn generate_households(&mut self) -> IncResult<()> {
+        let adult_ids: Vec<Uuid> = self
+            .people
+            .values()
+            .filter(|per| per.age >= 18)
+            .map(|per| per.id)
+            .collect();
+
+        for adult_id in adult_ids {
+            self.create_household(&[adult_id]);
+        }
+
+        let mut singles = self.get_singles_by_age(22, 70);
+        let seekers: Vec<(Uuid, i32)> = singles
+            .iter()
+            .flat_map(|(age, ids)| ids.iter().map(move |id| (*id, *age)))
+            .collect();
+
+        for (seeker_id, age) in seekers {
+            let ids = singles.entry(age).or_default();
+            let idx = match ids.iter().position(|id| *id == seeker_id) {
+                Some(idx) => idx,
+                None => continue, // Already partnered
+            };
+
+            if !self.rng.gen_bool(STARTING_PARTNER_PERCENTAGE / 2.) {
+                continue;
+            }
+            ids.swap_remove(idx);
+
+            if let Some(partner_id) = self.take_single_near_age(&mut singles, age) {
+                self.form_partnership(seeker_id, partner_id)?;
+            }
+        }
+
+        let mut adults_by_age: BTreeMap<i32, Vec<Uuid>> = BTreeMap::new();
+        for per in self.people.values().filter(|per| per.age >= 18) {
+            adults_by_age.entry(per.age).or_default().push(per.id);
+        }
+
+        let child_ids: Vec<(Uuid, i32)> = self
+            .people
+            .values()
+            .filter(|per| per.age < 18)
+            .map(|per| (per.id, per.age))
+            .collect();
+
+        for (child_id, age) in child_ids {
+            let mut parent_id = None;
+
+            for _ in 0..5 {
+                let parent_age = self.rng.gen_range(age + 20..=age + 40);
+                if let Some(ids) = adults_by_age.get(&parent_age).filter(|ids| !ids.is_empty()) {
+                    parent_id = Some(ids[self.rng.gen_range(0..ids.len())]);
+                    break;
+                }
+            }
+
+            let parent = match parent_id.and_then(|id| self.people.get(&id)) {
+                Some(parent) => parent,
+                None => {
+                    self.create_household(&[child_id]);
+                    continue;
+                }
+            };
+
+            let parent_ids: Vec<Uuid> = std::iter::once(parent.id)
+                .chain(parent.partner_id)
+                .collect();
+            let household_id = parent.household_id.ok_or(Error::DangerUnexpected)?;
+
+            for parent_id in &parent_ids {
+                if let Some(parent) = self.people.get_mut(parent_id) {
+                    parent.child_ids.push(child_id);
+                }
+            }
+            if let Some(child) = self.people.get_mut(&child_id) {
+                child.parent_ids = parent_ids;
+            }
+
+            self.join_household(child_id, household_id);
+        }
+
+        Ok(())
+    }
+
+    fn get_adult_ids(&self, household: &Household) -> Vec<Uuid> {
+        household
+            .member_ids
+            .iter()
+            .filter(|id| self.people.get(*id).is_some_and(|per| per.age >= 18))
+            .copied()
+            .collect()
+    }
+
+    /// Combined balance of the adults in each household, which their food is budgeted from
+    pub fn get_household_balances(&self) -> BTreeMap<Uuid, f32> {
+        self.households
+            .values()
+            .map(|household| {
+                let balance = self
+                    .get_adult_ids(household)
+                    .iter()
+                    .filter_map(|id| self.people.get(id))
+                    .map(|per| per.balance)
+                    .sum();
+                (household.id, balance)
+            })
+            .collect()
+    }
+
+    /// Splits the day's food bills evenly between the adults of each household,
+    /// so food is paid for out of the household's pooled funds
+    pub fn share_food_bills(&mut self, food_bills: &BTreeMap<Uuid, f32>) {
+        for household in self.households.values() {
+            let adults = self.get_adult_ids(household);
+            if adults.len() < 2 {
+                continue;
+            }
+
+            let total_bill: f32 = adults.iter().filter_map(|id| food_bills.get(id)).sum();
+            let share = total_bill / adults.len() as f32;
+            for adult_id in &adults {
+                if let Some(adult) = self.people.get_mut(adult_id) {
+                    adult.balance += food_bills.get(adult_id).unwrap_or(&0.) - share;
+                }
+            }
+        }
+    }
+
+    /// Shares what the adults in each household earned and paid this month, compared with their balance at
+    /// the start of the month, and pays for the children in it. Savings stay with whoever saved them.
+    /// Children with no adult to look after them are cared for by the government,
+    /// returns what that cost
+    pub fn settle_households(&mut self, opening_balances: &BTreeMap<Uuid, f32>) -> i64 {
+        let mut care_cost: f32 = 0.;
+
+        for household in self.households.values() {
+            let (adults, children): (Vec<Uuid>, Vec<Uuid>) = household
+                .member_ids
+                .iter()
+                .partition(|id| self.people.get(*id).is_some_and(|per| per.age >= 18));
+
+            if adults.is_empty() {
+                care_cost += CHILD_MONTHLY_COST * children.len() as f32;
+                continue;
+            }
+
+            let children_cost = CHILD_MONTHLY_COST * children.len() as f32;
+            let mut total_balance = -children_cost;
+            let mut total_income = -children_cost;
+            for per in adults.iter().filter_map(|id| self.people.get(id)) {
+                total_balance += per.balance;
+                total_income += per.balance - opening_balances.get(&per.id).unwrap_or(&per.balance);
+            }
+
+            let share = total_income / adults.len() as f32;
+            for adult_id in &adults {
+                if let Some(adult) = self.people.get_mut(adult_id) {
+                    let opening_balance = *opening_balances.get(adult_id).unwrap_or(&adult.balance);
+                    adult.balance = opening_balance + share;
+                }
+            }
+
+            // Children go without when the household is already in debt
+            for child_id in &children {
+                if let Some(child) = self.people.get_mut(child_id) {
+                    child.neglected = total_balance < 0.;
+                }
+            }
+        }
+
+        care_cost as i64
+    }
+
+    /// Adults who found a home of their own while living with someone else move out,
+    /// unless they live with their partner
+    pub fn move_out(&mut self, person_id: Uuid) {
+        let per = match self.people.get(&person_id) {
+            Some(per) => per,
+            None => return,
+        };
+
+        let household_size = per
+            .household_id
+            .and_then(|id| self.households.get(&id))
+            .map_or(0, |household| household.member_ids.len());
+
+        if per.age >= 18 && per.partner_id.is_none() && household_size > 1 {
+            self.create_household(&[person_id]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::person::housing::Housing;
+
+    fn person(age: i32, balance: f32) -> Person {
+        Person {
+            id: Uuid::new_v4(),
+            age,
+            balance,
+            ..Person::default()
+        }
+    }
+
+    fn add_people(state: &mut GameState, people: Vec<Person>) -> Vec<Uuid> {
+        people
+            .into_iter()
+            .map(|per| {
+                let id = per.id;
+                state.people.insert(id, per);
+                id
+            })
+            .collect()
+    }
+
+    #[test]
+    fn partner_without_a_home_moves_in_with_their_children() {
+        let mut state = GameState::default();
+        state.housing.occupied_homes = 1;
+
+        let mut owner = person(30, 0.);
+        owner.housing = Housing::Owned;
+        let mut mover = person(32, 0.);
+        mover.housing = Housing::Homeless;
+        let ids = add_people(
+            &mut state,
+            vec![mover, owner, person(5, 0.), person(19, 0.)],
+        );
+        let (mover_id, owner_id, child_id, adult_child_id) = (ids[0], ids[1], ids[2], ids[3]);
+
+        state.people.get_mut(&mover_id).unwrap().child_ids = vec![child_id, adult_child_id];
+        let mover_household = state.create_household(&[mover_id, child_id, adult_child_id]);
+        let owner_household = state.create_household(&[owner_id]);
+
+        state.form_partnership(mover_id, owner_id).unwrap();
+
+        assert_eq!(state.people[&mover_id].partner_id, Some(owner_id));
+        assert_eq!(state.people[&owner_id].partner_id, Some(mover_id));
+        assert!(state.people[&mover_id].housing == Housing::Dependent);
+        assert!(state.people[&owner_id].housing == Housing::Owned);
+        assert_eq!(state.housing.occupied_homes, 1);
+
+        assert_eq!(
+            state.households[&owner_household].member_ids,
+            vec![owner_id, mover_id, child_id]
+        );
+        assert_eq!(
+            state.households[&mover_household].member_ids,
+            vec![adult_child_id]
+        );
+    }
+
+    #[test]
+    fn partner_with_a_home_gives_it_up_when_both_have_one() {
+        let mut state = GameState::default();
+        state.housing.occupied_homes = 2;
+
+        let mut first = person(30, 0.);
+        first.housing = Housing::Owned;
+        let mut second = person(32, 0.);
+        second.housing = Housing::Renting {
+            rent: 500,
+            missed_payments: 0,
+        };
+        let ids = add_people(&mut state, vec![first, second]);
+        let first_household = state.create_household(&[ids[0]]);
+        state.create_household(&[ids[1]]);
+
+        state.form_partnership(ids[0], ids[1]).unwrap();
+
+        assert!(state.people[&ids[1]].housing == Housing::Dependent);
+        assert_eq!(state.housing.occupied_homes, 1);
+        assert_eq!(state.households[&first_household].member_ids, ids);
+        assert_eq!(state.households.len(), 1);
+    }
+
+    #[test]
+    fn newborn_joins_both_parents_and_their_household() {
+        let mut state = GameState::default();
+
+        let ids = add_people(&mut state, vec![person(28, 0.), person(30, 0.)]);
+        let (mother_id, partner_id) = (ids[0], ids[1]);
+        state.people.get_mut(&mother_id).unwrap().partner_id = Some(partner_id);
+        state.people.get_mut(&partner_id).unwrap().partner_id = Some(mother_id);
+        let household_id = state.create_household(&[mother_id, partner_id]);
+
+        let infant = person(0, 0.);
+        let infant_id = infant.id;
+        state.add_newborn(mother_id, infant);
+
+        assert_eq!(
+            state.people[&infant_id].parent_ids,
+            vec![mother_id, partner_id]
+        );
+        assert_eq!(state.people[&mother_id].child_ids, vec![infant_id]);
+        assert_eq!(state.people[&partner_id].child_ids, vec![infant_id]);
+        assert_eq!(state.people[&infant_id].household_id, Some(household_id));
+        assert!(state.households[&household_id]
+            .member_ids
+            .contains(&infant_id));
+    }
+
+    #[test]
+    fn households_share_this_months_income_but_not_savings() {
+        let mut state = GameState::default();
+
+        let ids = add_people(
+            &mut state,
+            vec![person(40, 13000.), person(38, 1000.), person(8, 0.)],
+        );
+        let (saver_id, partner_id, child_id) = (ids[0], ids[1], ids[2]);
+        state.create_household(&ids);
+
+        let opening_balances =
+            BTreeMap::from([(saver_id, 10000.), (partner_id, 0.), (child_id, 0.)]);
+        let care_cost = state.settle_households(&opening_balances);
+
+        // 4000 earned between them, less the child's costs, split in two
+        let share = (4000. - CHILD_MONTHLY_COST) / 2.;
+        assert_eq!(care_cost, 0);
+        assert_eq!(state.people[&saver_id].balance, 10000. + share);
+        assert_eq!(state.people[&partner_id].balance, share);
+        assert!(!state.people[&child_id].neglected);
+    }
+
+    #[test]
+    fn households_pay_for_food_from_pooled_funds() {
+        let mut state = GameState::default();
+
+        let ids = add_people(
+            &mut state,
+            vec![person(40, 1000.), person(38, 10.), person(8, 0.)],
+        );
+        let (earner_id, partner_id, child_id) = (ids[0], ids[1], ids[2]);
+        let household_id = state.create_household(&ids);
+        let single_id = add_people(&mut state, vec![person(30, 50.)])[0];
+        state.create_household(&[single_id]);
+
+        // The child's balance is not the household's to spend
+        assert_eq!(state.get_household_balances()[&household_id], 1010.);
+
+        // The partner could only afford a little food on their own, the household pays for it
+        let food_bills = BTreeMap::from([(earner_id, 4.), (partner_id, 4.), (single_id, 4.)]);
+        for (id, bill) in &food_bills {
+            state.people.get_mut(id).unwrap().balance -= bill;
+        }
+        state.share_food_bills(&food_bills);
+
+        assert_eq!(state.people[&earner_id].balance, 996.);
+        assert_eq!(state.people[&partner_id].balance, 6.);
+        assert_eq!(state.people[&child_id].balance, 0.);
+        assert_eq!(state.people[&single_id].balance, 46.);
+
+        // Only one of them ate today
+        let food_bills = BTreeMap::from([(partner_id, 4.)]);
+        state.people.get_mut(&partner_id).unwrap().balance -= 4.;
+        state.share_food_bills(&food_bills);
+        assert_eq!(state.people[&earner_id].balance, 994.);
+        assert_eq!(state.people[&partner_id].balance, 4.);
+    }
+
+    #[test]
+    fn children_without_adults_are_cared_for_by_the_government() {
+        let mut state = GameState::default();
+
+        let ids = add_people(&mut state, vec![person(30, -500.), person(4, 0.)]);
+        state.create_household(&ids);
+        let orphan_ids = add_people(&mut state, vec![person(10, 0.), person(12, 0.)]);
+        state.create_household(&orphan_ids);
+
+        let care_cost = state.settle_households(&BTreeMap::new());
+
+        assert_eq!(care_cost, (CHILD_MONTHLY_COST * 2.) as i64);
+        assert!(state.people[&ids[1]].neglected);
+        assert_eq!(state.people[&ids[0]].balance, -500. - CHILD_MONTHLY_COST);
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::structs::GameState;
+
+impl GameState {
+    /// Collects this month's debt repayments, then sums up what households owe for the finance app
+    pub fn pay_household_debts(&mut self) {
+        let debt_state = &mut self.household_debt;
+        debt_state.defaults_last_month = 0;
+        debt_state.new_credit_last_month = 0;
+
+        for per in self.people.values_mut() {
+            per.pay_debts(&mut self.housing, debt_state);
+        }
+
+        self.update_household_debt();
+    }
+
+    fn update_household_debt(&mut self) {
+        let debt_state = &mut self.household_debt;
+        debt_state.owed_by_type.clear();
+        debt_state.people_in_arrears = 0;
+
+        // People outside of a household are counted as a household of their own
+        let mut owed_by_household: BTreeMap<Uuid, f64> = BTreeMap::new();
+        let mut credit_score_total: i64 = 0;
+        let mut adult_count: i64 = 0;
+
+        for per in self.people.values() {
+            if per.age >= 18 {
+                credit_score_total += per.credit_score as i64;
+                adult_count += 1;
+            }
+
+            if per.debts.is_empty() {
+                continue;
+            }
+
+            for debt in &per.debts {
+                *debt_state.owed_by_type.entry(debt.debt_type).or_insert(0) += debt.owed as i64;
+            }
+
+            if per.debts.iter().any(|debt| debt.missed_payments > 0) {
+                debt_state.people_in_arrears += 1;
+            }
+
+            *owed_by_household
+                .entry(per.household_id.unwrap_or(per.id))
+                .or_insert(0.) += per.get_total_debt() as f64;
+        }
+
+        debt_state.total_owed = debt_state.owed_by_type.values().sum();
+        debt_state.indebted_households = owed_by_household.len() as i32;
+        debt_state.average_household_debt = match owed_by_household.len() {
+            0 => 0,
+            count => (owed_by_household.values().sum::<f64>() / count as f64) as i64,
+        };
+        debt_state.average_credit_score = match adult_count {
+            0 => 0,
+            count => (credit_score_total / count) as i32,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::person::{
+        debt::{Debt, DebtType, POOR_CREDIT_SCORE, STARTING_CREDIT_SCORE},
+        housing::Housing,
+        person::Person,
+    };
+
+    fn borrower(balance: f32, debt: Debt) -> Person {
+        Person {
+            id: Uuid::new_v4(),
+            age: 40,
+            balance,
+            credit_score: STARTING_CREDIT_SCORE,
+            debts: vec![debt],
+            ..Person::default()
+        }
+    }
+
+    #[test]
+    fn defaulting_on_a_mortgage_loses_the_home() {
+        let mut state = GameState::default();
+        state.housing.occupied_homes = 1;
+
+        let mut per = borrower(0., Debt::new(DebtType::Mortgage, 100000., 300, 70));
+        per.housing = Housing::Owned;
+        let per_id = per.id;
+        state.people.insert(per_id, per);
+
+        for _ in 0..2 {
+            state.pay_household_debts();
+        }
+        assert!(state.people[&per_id].housing == Housing::Owned);
+        assert_eq!(state.household_debt.people_in_arrears, 1);
+
+        state.pay_household_debts();
+
+        let per = &state.people[&per_id];
+        assert!(per.is_homeless());
+        assert!(per.debts.is_empty());
+        assert!(per.credit_score < POOR_CREDIT_SCORE);
+        assert_eq!(state.housing.occupied_homes, 0);
+        assert_eq!(state.household_debt.defaults_last_month, 1);
+        assert_eq!(state.household_debt.total_owed, 0);
+    }
+
+    #[test]
+    fn overdrawn_people_borrow_until_their_credit_is_poor() {
+        let mut state = GameState::default();
+
+        let mut per = borrower(-500., Debt::new(DebtType::Medical, 1000., 36, 70));
+        let per_id = per.id;
+        state.people.insert(per_id, per.clone());
+        state.pay_household_debts();
+
+        let borrowed = &state.people[&per_id];
+        assert_eq!(borrowed.balance, 0.);
+        assert!(borrowed
+            .debts
+            .iter()
+            .any(|debt| debt.debt_type == DebtType::ConsumerCredit));
+        assert_eq!(state.household_debt.new_credit_last_month, 500);
+
+        per.credit_score = POOR_CREDIT_SCORE - 1;
+        state.people.insert(per_id, per);
+        state.pay_household_debts();
+
+        assert!(state.people[&per_id].balance < 0.);
+        assert_eq!(state.household_debt.new_credit_last_month, 0);
+    }
+}
@@ -0,0 +1,162 @@
+use super::structs::{GameState, IncomeTaxState, TaxBracket};
+use crate::{
+    common::errors::{Error, IncResult},
+    entities::person::person::Person,
+};
+
+impl IncomeTaxState {
+    /// Splits the yearly tax on a salary into its bands. The first band is income between the
+    /// personal allowance and the first bracket, taxed at the basic rate, followed by one band per bracket
+    pub fn get_tax_by_band(&self, basic_rate: f32, salary: i32) -> Vec<f32> {
+        let mut tax_by_band = Vec::with_capacity(self.brackets.len() + 1);
+        let salary = salary as f32;
+
+        let mut band_start = self.personal_allowance as f32;
+        let mut band_rate = basic_rate;
+        for bracket in &self.brackets {
+            let band_end = bracket.threshold as f32;
+            tax_by_band.push((salary.min(band_end) - band_start).max(0.) * band_rate);
+
+            band_start = band_start.max(band_end);
+            band_rate = bracket.rate;
+        }
+        tax_by_band.push((salary - band_start).max(0.) * band_rate);
+
+        tax_by_band
+    }
+
+    /// Share of the salary paid in tax across all bands
+    pub fn get_tax_rate(&self, basic_rate: f32, salary: i32) -> f32 {
+        if salary <= 0 {
+            return 0.;
+        }
+
+        self.get_tax_by_band(basic_rate, salary).iter().sum::<f32>() / salary as f32
+    }
+
+    /// Clears last month's revenue, keeping a slot for every band
+    pub fn reset_revenue(&mut self) {
+        self.revenue_by_band = vec![0; self.brackets.len() + 1];
+    }
+}
+
+impl GameState {
+    /// Replaces the tax code with a new personal allowance and brackets, returning the income expected from people
+    pub fn update_income_tax(
+        &mut self,
+        personal_allowance: i32,
+        brackets: Vec<TaxBracket>,
+    ) -> IncResult<i64> {
+        if personal_allowance < 0 {
+            return Err(Error::Danger(
+                "The personal allowance can not be negative.".to_string(),
+            ));
+        }
+
+        let mut previous_threshold = personal_allowance;
+        for bracket in &brackets {
+            if bracket.threshold <= previous_threshold {
+                return Err(Error::Danger(
+                    "Each tax bracket must start above the one before it and the personal allowance."
+                        .to_string(),
+                ));
+            }
+
+            if !(0. ..=1.).contains(&bracket.rate) {
+                return Err(Error::Danger(
+                    "Tax bracket rates must be between 0 and 100%.".to_string(),
+                ));
+            }
+
+            previous_threshold = bracket.threshold;
+        }
+
+        self.income_tax.personal_allowance = personal_allowance;
+        self.income_tax.brackets = brackets;
+        self.income_tax.reset_revenue();
+
+        self.finance_data.expected_person_income = self.get_expected_person_income();
+        Ok(self.finance_data.expected_person_income)
+    }
+
+    /// Monthly income tax expected from everyone's current salary
+    pub fn get_expected_person_income(&self) -> i64 {
+        self.people
+            .values()
+            .map(|per| {
+                let tax_rate = Person::get_tax_rate(
+                    &self.income_tax,
+                    &self.rules.tax_rule,
+                    self.tax_rate,
+                    per.salary,
+                );
+                ((per.salary as f32 / 12.) * tax_rate) as i64
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::structs::TaxRule;
+
+    fn tax_code() -> IncomeTaxState {
+        IncomeTaxState {
+            personal_allowance: 10000,
+            brackets: vec![
+                TaxBracket {
+                    threshold: 50000,
+                    rate: 0.4,
+                },
+                TaxBracket {
+                    threshold: 150000,
+                    rate: 0.5,
+                },
+            ],
+            revenue_by_band: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn brackets_only_tax_income_inside_them() {
+        let income_tax = tax_code();
+
+        assert_eq!(income_tax.get_tax_by_band(0.2, 8000), vec![0., 0., 0.]);
+        assert_eq!(income_tax.get_tax_by_band(0.2, 30000), vec![4000., 0., 0.]);
+        assert_eq!(
+            income_tax.get_tax_by_band(0.2, 200000),
+            vec![8000., 40000., 25000.]
+        );
+        assert_eq!(income_tax.get_tax_rate(0.2, 0), 0.);
+    }
+
+    #[test]
+    fn tax_rule_replaces_the_basic_rate() {
+        let income_tax = tax_code();
+        let rule = TaxRule {
+            enabled: true,
+            minimum_salary: 40000,
+            tax_rate: 0.3,
+        };
+
+        assert_eq!(Person::get_basic_tax_rate(&rule, 0.2, 30000), 0.2);
+
+        let basic_rate = Person::get_basic_tax_rate(&rule, 0.2, 60000);
+        assert_eq!(
+            income_tax.get_tax_by_band(basic_rate, 60000),
+            vec![12000., 4000., 0.]
+        );
+    }
+
+    #[test]
+    fn brackets_must_be_in_order() {
+        let mut state = GameState::default();
+        let brackets = tax_code().brackets.into_iter().rev().collect();
+
+        assert!(state.update_income_tax(10000, brackets).is_err());
+        assert!(state.update_income_tax(60000, tax_code().brackets).is_err());
+        assert!(state.update_income_tax(10000, tax_code().brackets).is_ok());
+        assert_eq!(state.income_tax.revenue_by_band.len(), 3);
+    }
+}
@@ -0,0 +1,429 @@
+use rand::seq::SliceRandom;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+};
+use uuid::Uuid;
+
+use super::{generation::get_expected_salary_range, structs::GameState};
+use crate::{
+    common::{
+        config::Config,
+        errors::{Error, IncResult},
+        util::{chance_one_in, generate_unemployed_salary},
+    },
+    entities::{
+        business::Business,
+        person::person::{EducationLevel, Job, Person},
+    },
+};
+
+const QUIT_WELFARE: i32 = 30; // Employees below this welfare may quit their job
+const MONTHLY_QUIT_CHANCE: i32 = 6;
+const MONTHLY_SEARCH_CHANCE: i32 = 10; // How often employees look around for a better job
+const SWITCH_RAISE: f32 = 0.1; // Employees only switch jobs for at least this much more pay
+const RESERVATION_PERCENTAGE: f32 = 0.6; // Part of the lowest salary for their education the unemployed will work for
+
+/// Whether the individual could take a job at the business asking for the education level
+fn is_qualified(per: &Person, bid: Uuid, minimum_education_level: EducationLevel) -> bool {
+    (per.education_level as u8) >= (minimum_education_level as u8)
+        && match per.job {
+            Job::Unemployed => true,
+            Job::Employee(current_bid) => current_bid != bid,
+            _ => false,
+        }
+}
+
+/// Whether the individual would take a job asking for the education level at the offered salary
+fn accepts_offer(
+    per: &Person,
+    bid: Uuid,
+    minimum_education_level: EducationLevel,
+    salary_offer: i32,
+    config: &Config,
+) -> bool {
+    if !is_qualified(per, bid, minimum_education_level) {
+        return false;
+    }
+
+    match per.job {
+        Job::Unemployed => {
+            let reservation_salary = get_expected_salary_range(config, &per.education_level).start
+                as f32
+                * RESERVATION_PERCENTAGE;
+
+            salary_offer as f32 >= reservation_salary
+        }
+        _ => salary_offer as f32 >= per.salary as f32 * (1. + SWITCH_RAISE),
+    }
+}
+
+/// Staff already working at the business get the salary it offers new hires
+fn pay_staff_the_offer(business: &Business, people: &mut BTreeMap<Uuid, Person>) {
+    for emp_id in &business.employees {
+        if let Some(emp) = people.get_mut(emp_id) {
+            emp.set_salary(business.employee_salary);
+        }
+    }
+}
+
+impl GameState {
+    /// Runs monthly after businesses have found their new market. Unhappy employees quit,
+    /// businesses post their open jobs and the best paid are filled first by the unemployed and
+    /// employees looking for a better job
+    pub fn run_labour_market(&mut self, config: &Config) -> IncResult<()> {
+        let market = &mut self.labour_market;
+        market.hires_last_month = 0;
+        market.quits_last_month = 0;
+        market.job_switches_last_month = 0;
+        market.minimum_wage_layoffs_last_month = 0;
+
+        for per in self.people.values_mut() {
+            let bid = match per.job {
+                Job::Employee(bid) => bid,
+                _ => continue,
+            };
+
+            if per.welfare >= QUIT_WELFARE || !chance_one_in(MONTHLY_QUIT_CHANCE, &mut self.rng) {
+                continue;
+            }
+
+            if let Some(business) = self.businesses.get_mut(&bid) {
+                business.employees.retain(|emp_id| *emp_id != per.id);
+            }
+            per.job = Job::Unemployed;
+            per.set_salary(generate_unemployed_salary(&mut self.rng));
+            market.quits_last_month += 1;
+        }
+
+        self.enforce_minimum_wage()?;
+
+        let rng = &mut self.rng;
+        let mut seekers: Vec<Uuid> = self
+            .people
+            .values()
+            .filter(|per| {
+                per.age >= 18
+                    && match per.job {
+                        Job::Unemployed => true,
+                        Job::Employee(_) => chance_one_in(MONTHLY_SEARCH_CHANCE, rng),
+                        _ => false,
+                    }
+            })
+            .map(|per| per.id)
+            .collect();
+        seekers.shuffle(rng);
+
+        // Job seekers with at least the education each business asks for
+        let mut labour_pools: HashMap<EducationLevel, i32> = HashMap::new();
+        for business in self.businesses.values() {
+            let level = business.minimum_education_level;
+            labour_pools.entry(level).or_insert_with(|| {
+                seekers
+                    .iter()
+                    .filter(|seeker_id| {
+                        self.people
+                            .get(seeker_id)
+                            .is_some_and(|per| (per.education_level as u8) >= (level as u8))
+                    })
+                    .count() as i32
+            });
+        }
+
+        let minimum_wage = Business::get_minimum_wage(&self.rules.minimum_wage_rule);
+        let wage_index = self.prices.wage_index;
+        let mut vacancies: Vec<(Uuid, i32)> = Vec::new();
+        for business in self.businesses.values_mut() {
+            let salary = business.employee_salary;
+            let labour_pool = labour_pools[&business.minimum_education_level];
+            let openings = business.post_vacancies(config, wage_index, minimum_wage, labour_pool);
+
+            if business.employee_salary > salary {
+                pay_staff_the_offer(business, &mut self.people);
+            }
+
+            if openings > 0 {
+                vacancies.push((business.id, openings));
+            }
+        }
+        vacancies.sort_by_key(|(bid, _)| Reverse(self.businesses[bid].employee_salary));
+
+        for (bid, openings) in vacancies {
+            let business = self.businesses.get(&bid).ok_or(Error::DangerUnexpected)?;
+            let (minimum_education_level, salary_offer) =
+                (business.minimum_education_level, business.employee_salary);
+
+            let mut hired: Vec<Uuid> = Vec::new();
+            let mut turned_down = false;
+            seekers.retain(|seeker_id| {
+                let per = match self.people.get(seeker_id) {
+                    Some(per) if hired.len() < openings as usize => per,
+                    _ => return true,
+                };
+                if !is_qualified(per, bid, minimum_education_level) {
+                    return true;
+                }
+
+                let accepts =
+                    accepts_offer(per, bid, minimum_education_level, salary_offer, config);
+                if accepts {
+                    hired.push(*seeker_id);
+                } else {
+                    turned_down = true;
+                }
+                !accepts
+            });
+
+            for per_id in &hired {
+                let per = self.people.get_mut(per_id).ok_or(Error::DangerUnexpected)?;
+
+                if let Job::Employee(old_bid) = per.job {
+                    if let Some(old_business) = self.businesses.get_mut(&old_bid) {
+                        old_business.employees.retain(|emp_id| emp_id != per_id);
+                    }
+                    self.labour_market.job_switches_last_month += 1;
+                }
+
+                per.job = Job::Employee(bid);
+                per.set_salary(salary_offer);
+            }
+
+            let business = self
+                .businesses
+                .get_mut(&bid)
+                .ok_or(Error::DangerUnexpected)?;
+            business.employees.extend(&hired);
+            business.vacancies -= hired.len() as i32;
+            self.labour_market.hires_last_month += hired.len() as i32;
+
+            // Pay only goes up when people who could have filled the jobs asked for more
+            if turned_down && business.vacancies > 0 {
+                business.raise_salary_offer(config, wage_index);
+                pay_staff_the_offer(business, &mut self.people);
+            }
+        }
+
+        self.labour_market.vacancies = self.businesses.values().map(|b| b.vacancies).sum();
+
+        Ok(())
+    }
+
+    /// Businesses paying less than the minimum wage raise their pay, letting go of staff they can not afford
+    fn enforce_minimum_wage(&mut self) -> IncResult<()> {
+        let minimum_wage = Business::get_minimum_wage(&self.rules.minimum_wage_rule);
+        if minimum_wage <= 0 {
+            return Ok(());
+        }
+
+        for business in self.businesses.values_mut() {
+            self.labour_market.minimum_wage_layoffs_last_month +=
+                business.pay_minimum_wage(minimum_wage, &mut self.people, &mut self.rng)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::config::{load_config, ConfigRange, EducationConfig},
+        game::structs::MinimumWageRule,
+    };
+
+    fn education(min: i32, max: i32) -> EducationConfig {
+        EducationConfig {
+            chance: 0,
+            salary_range: ConfigRange { min, max },
+        }
+    }
+
+    #[test]
+    fn employees_only_switch_for_better_pay_at_another_business() {
+        let mut config = load_config().unwrap();
+        config.no_education = education(20000, 30000);
+
+        let (bid, other_bid) = (Uuid::new_v4(), Uuid::new_v4());
+        let employee = Person {
+            job: Job::Employee(bid),
+            salary: 20000,
+            ..Person::default()
+        };
+
+        let offer = |bid, salary| {
+            accepts_offer(
+                &employee,
+                bid,
+                EducationLevel::NoFormalEducation,
+                salary,
+                &config,
+            )
+        };
+        assert!(!offer(other_bid, 21000));
+        assert!(offer(other_bid, 23000));
+        assert!(!offer(bid, 30000));
+
+        // The unemployed take anything above part of the lowest salary for their education
+        let unemployed = Person::default();
+        assert!(accepts_offer(
+            &unemployed,
+            bid,
+            EducationLevel::NoFormalEducation,
+            13000,
+            &config
+        ));
+        assert!(!accepts_offer(
+            &unemployed,
+            bid,
+            EducationLevel::NoFormalEducation,
+            11000,
+            &config
+        ));
+    }
+
+    #[test]
+    fn salary_offers_are_raised_but_never_cut() {
+        let mut config = load_config().unwrap();
+        config.no_education = education(20000, 30000);
+
+        let mut business = Business {
+            employee_salary: 25000,
+            ..Business::default()
+        };
+        business.raise_salary_offer(&config, 1.);
+        assert_eq!(business.employee_salary, 25750);
+        business.employee_salary = 29500;
+        business.raise_salary_offer(&config, 1.);
+        assert_eq!(business.employee_salary, 30000);
+
+        // Already paying above the expected range
+        business.employee_salary = 40000;
+        business.raise_salary_offer(&config, 1.);
+        business.post_vacancies(&config, 1., 0, 0);
+        assert_eq!(business.employee_salary, 40000);
+
+        // Pay below the expected range or the minimum wage is brought up to it
+        business.employee_salary = 15000;
+        business.post_vacancies(&config, 1., 0, 0);
+        assert_eq!(business.employee_salary, 20000);
+        business.post_vacancies(&config, 1., 22000, 0);
+        assert_eq!(business.employee_salary, 22000);
+    }
+
+    #[test]
+    fn openings_are_limited_by_the_balance_and_the_labour_pool() {
+        let mut config = load_config().unwrap();
+        config.no_education = education(24000, 30000);
+
+        // Expected income pays for 25 staff, the balance for five of them for six months
+        let mut business = Business {
+            employee_salary: 24000,
+            expected_income: 100000,
+            employee_budget_allocation: 0.5,
+            balance: 60000.,
+            ..Business::default()
+        };
+        assert_eq!(business.post_vacancies(&config, 1., 0, 100), 5);
+        assert_eq!(business.post_vacancies(&config, 1., 0, 3), 3);
+
+        business.employees = vec![Uuid::new_v4(); 4];
+        assert_eq!(business.post_vacancies(&config, 1., 0, 100), 1);
+
+        business.balance = -1000.;
+        assert_eq!(business.post_vacancies(&config, 1., 0, 100), 0);
+        assert_eq!(business.vacancies, 0);
+    }
+
+    #[test]
+    fn pay_only_rises_when_qualified_seekers_turn_the_offer_down() {
+        let mut config = load_config().unwrap();
+        config.no_education = education(10000, 15000);
+        config.college = education(20000, 30000);
+        config.bachelors = education(50000, 80000);
+
+        let mut state = GameState::default();
+        let business = Business {
+            id: Uuid::new_v4(),
+            minimum_education_level: EducationLevel::College,
+            employee_salary: 20000,
+            expected_income: 100000,
+            employee_budget_allocation: 0.5,
+            balance: 100000.,
+            ..Business::default()
+        };
+        let bid = business.id;
+        state.businesses.insert(bid, business);
+
+        let add_seeker = |state: &mut GameState, education_level| {
+            let per = Person {
+                id: Uuid::new_v4(),
+                age: 30,
+                education_level,
+                ..Person::default()
+            };
+            state.people.insert(per.id, per);
+        };
+
+        // Nobody could fill the jobs, so none are open and pay stays
+        add_seeker(&mut state, EducationLevel::NoFormalEducation);
+        state.run_labour_market(&config).unwrap();
+        assert_eq!(state.businesses[&bid].employee_salary, 20000);
+        assert_eq!(state.labour_market.vacancies, 0);
+
+        // A graduate holds out for more than the business offers
+        add_seeker(&mut state, EducationLevel::Bachelors);
+        state.run_labour_market(&config).unwrap();
+        assert_eq!(state.businesses[&bid].employee_salary, 20600);
+        assert_eq!(state.labour_market.vacancies, 1);
+        assert_eq!(state.labour_market.hires_last_month, 0);
+    }
+
+    #[test]
+    fn minimum_wage_lays_off_staff_the_business_can_not_afford() {
+        let mut state = GameState::default();
+        state.rules.minimum_wage_rule = MinimumWageRule {
+            enabled: true,
+            minimum_wage: 24000,
+        };
+
+        // Expected income pays for two staff at the minimum wage
+        let mut business = Business {
+            id: Uuid::new_v4(),
+            employee_salary: 12000,
+            expected_income: 10000,
+            employee_budget_allocation: 0.4,
+            ..Business::default()
+        };
+        for _ in 0..4 {
+            let per = Person {
+                id: Uuid::new_v4(),
+                job: Job::Employee(business.id),
+                salary: 12000,
+                ..Person::default()
+            };
+            business.employees.push(per.id);
+            state.people.insert(per.id, per);
+        }
+        let bid = business.id;
+        state.businesses.insert(bid, business);
+
+        state.enforce_minimum_wage().unwrap();
+
+        let business = &state.businesses[&bid];
+        assert_eq!(business.employee_salary, 24000);
+        assert_eq!(business.employees.len(), 2);
+        assert!(business
+            .employees
+            .iter()
+            .all(|emp_id| state.people[emp_id].salary == 24000));
+        assert_eq!(state.labour_market.minimum_wage_layoffs_last_month, 2);
+
+        let unemployed = state
+            .people
+            .values()
+            .filter(|per| per.job == Job::Unemployed)
+            .count();
+        assert_eq!(unemployed, 2);
+    }
+}
@@ -1,97 +1,452 @@
+#[cfg(feature = "desktop")]
 use crate::common::{
-    config::{load_config, Config},
-    errors::{Error, IncResult, Severity},
+    config::load_config,
+    errors::IncResult,
+    filesystem::{read_save, validate_save_name, write_save},
+};
+use crate::common::{
+    config::Config,
+    errors::{Error, Severity},
     payloads::PayloadNewDay,
 };
-use serde_json::json;
-use std::{time::Duration, vec};
-use tauri::{Manager, State};
+use std::time::Duration;
+#[cfg(feature = "desktop")]
+use tauri::State;
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio_util::sync::CancellationToken;
 
+#[cfg(feature = "desktop")]
 use super::{
+    control::{ControlMessage, GameSpeed},
     generation::{generate_game, stabilize_game},
+};
+use super::{
+    control::{GameControl, LoopControl, LoopReceiver},
+    sink::{GameEvent, GameEventSink},
     state_manager::GameStateSafe,
+    structs::GameState,
 };
 
-#[tauri::command] // TODO: Take in game name as argument and call "create_save(name)"
+const SHUTDOWN_DELAY: Duration = Duration::from_secs(10); // How long the player has to read a fatal error before the game stops
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
 pub async fn create_game(
     state_mux: State<'_, GameStateSafe>,
+    game_control: State<'_, GameControl>,
     app_handle: tauri::AppHandle,
+    name: String,
+    seed: Option<u64>,
 ) -> IncResult<()> {
-    let config = load_config().unwrap();
-    app_handle
-        .emit_all(
-            "loading_status",
-            json!({
-                "Generating Game": []
-            }),
-        )
-        .unwrap();
-
-    generate_game(&state_mux, &config, &app_handle)?;
-
-    app_handle.emit_all("loading_status", json!({
-        "Checking everything is stable": ["Checking busineses", "Checking jobs & salaries", "Checking economy is stable", "Checking welfare is sufficient", "Checking hospital capacity is sufficient"]
-    })).unwrap();
+    // Checked before generating, which takes a while, rather than when the game is first saved
+    validate_save_name(&name)?;
+    let config = load_config()?;
+
+    let receiver = game_control.start()?;
+    *state_mux.lock().unwrap() = GameState::default(); // Anything left from a previous game would be generated on top of
+
+    let seed = seed.unwrap_or_else(rand::random); // The same seed will always generate the same game
+    app_handle.emit(GameEvent::LoadingStatus {
+        stage: "Generating Game",
+        steps: &[],
+    })?;
+
+    generate_game(&state_mux, &config, &app_handle, seed)?;
+
+    app_handle.emit(GameEvent::LoadingStatus {
+        stage: "Checking everything is stable",
+        steps: &[
+            "Checking busineses",
+            "Checking jobs & salaries",
+            "Checking economy is stable",
+            "Checking welfare is sufficient",
+            "Checking hospital capacity is sufficient",
+        ],
+    })?;
 
     stabilize_game(&state_mux, &config, &app_handle)?;
 
-    app_handle.emit_all("game_generated", ()).unwrap();
-    app_handle.emit_all("open_debugger_app", ()).unwrap(); // Only in debug mode
+    {
+        let mut state = state_mux.lock().unwrap();
+
+        // The game was reset while it was being generated, what was generated belongs to no game
+        if receiver.cancel.is_cancelled() {
+            *state = GameState::default();
+            return Ok(());
+        }
+
+        state.name = name;
+        write_save(&state.name, &state)?;
+    }
+
+    app_handle.emit(GameEvent::GameGenerated)?;
+    app_handle.emit(GameEvent::OpenDebuggerApp)?;
 
-    start_game_loop(&state_mux, &app_handle, &config).await;
+    start_game_loop(&state_mux, &app_handle, &config, receiver).await;
     Ok(())
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn reset_game(app_handle: tauri::AppHandle) {
-    // do stuff
+pub async fn load_game(
+    state_mux: State<'_, GameStateSafe>,
+    game_control: State<'_, GameControl>,
+    app_handle: tauri::AppHandle,
+    name: String,
+) -> IncResult<()> {
+    let receiver = game_control.start()?;
+
+    let config = load_config()?;
+    let state = read_save(&name)?;
+
+    {
+        let mut state_lock = state_mux.lock().unwrap();
+        if receiver.cancel.is_cancelled() {
+            return Ok(()); // The game was reset while the save was being read
+        }
+
+        *state_lock = state;
+    }
+
+    app_handle.emit(GameEvent::GameGenerated)?;
+
+    start_game_loop(&state_mux, &app_handle, &config, receiver).await;
+    Ok(())
 }
 
-fn emit_error(app_handle: &tauri::AppHandle, error: &Error) {
-    app_handle.emit_all("error", &error).unwrap();
+/// Stops the running game and clears the game state, so a new game can be created or loaded
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn reset_game(
+    state_mux: State<'_, GameStateSafe>,
+    game_control: State<'_, GameControl>,
+) -> IncResult<()> {
+    stop_game(&state_mux, &game_control);
+    Ok(())
 }
 
-pub async fn start_game_loop(
+/// Cancels the running game loop and clears the game state it was running
+pub fn stop_game(state_mux: &GameStateSafe, game_control: &GameControl) {
+    game_control.stop();
+    *state_mux.lock().unwrap() = GameState::default();
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn pause_game(game_control: State<'_, GameControl>) -> IncResult<()> {
+    game_control.send(ControlMessage::Pause)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn resume_game(game_control: State<'_, GameControl>) -> IncResult<()> {
+    game_control.send(ControlMessage::Resume)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn set_game_speed(game_control: State<'_, GameControl>, speed: GameSpeed) -> IncResult<()> {
+    game_control.send(ControlMessage::SetSpeed(speed))
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn step_game_day(game_control: State<'_, GameControl>) -> IncResult<()> {
+    game_control.send(ControlMessage::StepDay)
+}
+
+/// Sends the error to the frontend, returns true if the game can not continue
+fn report_error(sink: &dyn GameEventSink, err: Error) -> bool {
+    let fatal = err.severity() == Severity::Fatal as u8;
+
+    // If the error can not be shown there is nothing else to tell the player with, so the game must stop
+    sink.emit(GameEvent::Error(err)).is_err() || fatal
+}
+
+/// Waits until the next day is due, applying any control messages that arrive in the meantime.
+/// Returns false once the loop has been cancelled or can no longer be controlled, which means it should stop
+async fn wait_for_next_day(control: &mut LoopControl, receiver: &mut LoopReceiver) -> bool {
+    loop {
+        if control.paused {
+            // A paused game only wakes up for the next message
+            tokio::select! {
+                _ = receiver.cancel.cancelled() => return false,
+                message = receiver.messages.recv() => match message {
+                    Some(message) => {
+                        if control.apply(message) {
+                            return true;
+                        }
+                    }
+                    None => return false,
+                },
+            }
+
+            continue;
+        }
+
+        match control.speed.day_duration() {
+            Some(day_duration) => {
+                tokio::select! {
+                    _ = receiver.cancel.cancelled() => return false,
+                    _ = tokio::time::sleep(day_duration) => return true,
+                    message = receiver.messages.recv() => match message {
+                        Some(message) => {
+                            if control.apply(message) {
+                                return true;
+                            }
+                        }
+                        None => return false,
+                    },
+                }
+            }
+            None => {
+                tokio::task::yield_now().await; // Gives commands a chance to use the game state between days
+
+                if receiver.cancel.is_cancelled() {
+                    return false;
+                }
+
+                loop {
+                    match receiver.messages.try_recv() {
+                        Ok(message) => {
+                            control.apply(message);
+                        }
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => return false,
+                    }
+                }
+
+                if !control.paused {
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+/// Runs a single day of the game, returns false if the game can not continue
+pub fn pass_day(
     state_mux: &GameStateSafe,
-    app_handle: &tauri::AppHandle,
+    sink: &dyn GameEventSink,
     config: &Config,
-) {
-    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    cancel: &CancellationToken,
+) -> bool {
+    let state = &mut state_mux.lock().unwrap();
 
-    loop {
-        interval.tick().await;
+    // The game may have been reset while waiting for the lock, the state no longer belongs to this loop
+    if cancel.is_cancelled() {
+        return false;
+    }
 
-        let state = &mut state_mux.lock().unwrap();
+    state.date.new_day();
+    let date_string = state.date.get_date_string();
+    let new_day_res = sink.emit(GameEvent::NewDay(PayloadNewDay { date: date_string }));
 
-        state.date.new_day();
-        let date_string = state.date.get_date_string();
-        app_handle
-            .emit_all("new_day", PayloadNewDay { date: date_string })
-            .unwrap();
+    if let Err(err) = new_day_res {
+        if report_error(sink, err) {
+            return false;
+        }
+    }
 
-        let day = state.date.day;
-        let day_res = state.day_pass(day, Some(app_handle), config);
+    let day = state.date.day;
+    let day_res = state.day_pass(day, sink, config);
+
+    if let Err(err) = day_res {
+        if report_error(sink, err) {
+            return false;
+        }
+    }
 
-        if let Err(err) = day_res {
-            emit_error(app_handle, &err);
+    if state.date.on_new_month {
+        let month_res = state.month_pass(sink, config);
 
-            if err.severity() == Severity::Fatal as u8 {
-                // TODO: wait before quitting
-                break;
+        if let Err(err) = month_res {
+            if report_error(sink, err) {
+                return false;
             }
         }
+    }
 
-        if state.date.on_new_month {
-            let month_res = state.month_pass(app_handle, config);
+    true
+}
 
-            if let Err(err) = month_res {
-                emit_error(app_handle, &err);
+/// Gives the player time to read why the game is stopping, then stops the game and clears its state
+async fn shut_down(
+    state_mux: &GameStateSafe,
+    sink: &dyn GameEventSink,
+    cancel: &CancellationToken,
+) {
+    // Emission failures are ignored from here, the game is stopping either way
+    let _ = sink.emit(GameEvent::ShutdownScheduled {
+        seconds: SHUTDOWN_DELAY.as_secs(),
+    });
 
-                if err.severity() == Severity::Fatal as u8 {
-                    // TODO: wait before quitting
-                    break;
-                }
+    tokio::select! {
+        _ = cancel.cancelled() => return, // The player reset the game themselves
+        _ = tokio::time::sleep(SHUTDOWN_DELAY) => {}
+    }
+
+    cancel.cancel();
+    *state_mux.lock().unwrap() = GameState::default();
+
+    let _ = sink.emit(GameEvent::GameShutdown);
+}
+
+pub async fn start_game_loop(
+    state_mux: &GameStateSafe,
+    sink: &dyn GameEventSink,
+    config: &Config,
+    mut receiver: LoopReceiver,
+) {
+    let mut control = LoopControl::default();
+
+    while wait_for_next_day(&mut control, &mut receiver).await {
+        if !pass_day(state_mux, sink, config, &receiver.cancel) {
+            if !receiver.cancel.is_cancelled() {
+                shut_down(state_mux, sink, &receiver.cancel).await;
             }
+
+            break;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::control::{ControlMessage, GameSpeed};
+    use std::sync::{Arc, Mutex};
+    use tokio::time::{timeout, Instant};
+
+    const BLOCKED: Duration = Duration::from_millis(100); // Long enough to tell a waiting loop from one that has moved on
+
+    async fn next_day_within(
+        duration: Duration,
+        control: &mut LoopControl,
+        receiver: &mut LoopReceiver,
+    ) -> Option<bool> {
+        timeout(duration, wait_for_next_day(control, receiver))
+            .await
+            .ok()
+    }
+
+    #[tokio::test]
+    async fn paused_game_waits_until_resumed() {
+        let game_control = GameControl::default();
+        let mut receiver = game_control.start().unwrap();
+        let mut control = LoopControl {
+            paused: true,
+            speed: GameSpeed::Max,
+        };
+
+        assert_eq!(
+            next_day_within(BLOCKED, &mut control, &mut receiver).await,
+            None
+        );
+
+        game_control.send(ControlMessage::Resume).unwrap();
+        assert_eq!(
+            next_day_within(BLOCKED, &mut control, &mut receiver).await,
+            Some(true)
+        );
+        assert!(!control.paused);
+    }
+
+    #[tokio::test]
+    async fn step_runs_exactly_one_day_while_paused() {
+        let game_control = GameControl::default();
+        let mut receiver = game_control.start().unwrap();
+        let mut control = LoopControl {
+            paused: true,
+            speed: GameSpeed::Normal,
+        };
+
+        game_control.send(ControlMessage::StepDay).unwrap();
+        assert_eq!(
+            next_day_within(BLOCKED, &mut control, &mut receiver).await,
+            Some(true)
+        );
+
+        assert!(control.paused);
+        assert_eq!(
+            next_day_within(BLOCKED, &mut control, &mut receiver).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn speed_changes_apply_to_the_day_being_waited_for() {
+        let game_control = GameControl::default();
+        let mut receiver = game_control.start().unwrap();
+        let mut control = LoopControl::default();
+
+        // A normal day lasts a second, switching to max speed should not wait that long
+        game_control
+            .send(ControlMessage::SetSpeed(GameSpeed::Max))
+            .unwrap();
+        assert_eq!(
+            next_day_within(BLOCKED, &mut control, &mut receiver).await,
+            Some(true)
+        );
+        assert_eq!(control.speed, GameSpeed::Max);
+
+        // Days at max speed run back to back, picking up the new speed on the way
+        game_control
+            .send(ControlMessage::SetSpeed(GameSpeed::Fast))
+            .unwrap();
+        assert!(wait_for_next_day(&mut control, &mut receiver).await);
+        assert_eq!(control.speed, GameSpeed::Fast);
+
+        let started = Instant::now();
+        assert!(wait_for_next_day(&mut control, &mut receiver).await);
+        assert!(started.elapsed() >= GameSpeed::Fast.day_duration().unwrap());
+    }
+
+    #[test]
+    fn only_one_game_runs_at_a_time() {
+        let game_control = GameControl::default();
+        let _receiver = game_control.start().unwrap();
+
+        assert!(game_control.start().is_err());
+
+        game_control.stop();
+        assert!(game_control.start().is_ok());
+    }
+
+    #[test]
+    fn stopping_the_game_clears_its_state() {
+        let state_mux: GameStateSafe = Arc::new(Mutex::new(GameState::default()));
+        let game_control = GameControl::default();
+        let receiver = game_control.start().unwrap();
+        {
+            let mut state = state_mux.lock().unwrap();
+            state.name = "running".to_string();
+            state.government_balance = 1000;
+        }
+
+        stop_game(&state_mux, &game_control);
+
+        assert!(receiver.cancel.is_cancelled());
+        assert_eq!(
+            serde_json::to_value(&*state_mux.lock().unwrap()).unwrap(),
+            serde_json::to_value(GameState::default()).unwrap()
+        );
+        assert!(game_control.send(ControlMessage::Pause).is_err());
+    }
+
+    #[tokio::test]
+    async fn stopped_game_does_not_wait_for_another_day() {
+        let game_control = GameControl::default();
+        let mut receiver = game_control.start().unwrap();
+        let mut control = LoopControl {
+            paused: true,
+            speed: GameSpeed::Normal,
+        };
+
+        game_control.stop();
+        assert_eq!(
+            next_day_within(BLOCKED, &mut control, &mut receiver).await,
+            Some(false)
+        );
+    }
+}
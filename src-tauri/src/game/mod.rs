@@ -1,5 +1,17 @@
+pub mod bankruptcy;
+pub mod control;
+pub mod epidemic;
+pub mod estate;
 pub mod events;
 pub mod generation;
+pub mod household;
+pub mod household_debt;
+pub mod income_tax;
+pub mod labour_market;
 pub mod manager;
+pub mod prices;
+pub mod public_debt;
+pub mod rules;
+pub mod sink;
 pub mod state_manager;
 pub mod structs;
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+
+use super::structs::GameState;
+use crate::entities::business::{Business, ProductType};
+
+const INDEX_HISTORY_MONTHS: usize = 12;
+const MAXIMUM_MONTHLY_INDEXATION: f32 = 0.02; // Most wages catch up with prices in a month
+
+impl GameState {
+    /// Runs monthly after businesses have worked out last month's income. Businesses set their prices for the next
+    /// month, the price index moves with the changes they made, then wages catch up with it
+    pub fn update_prices(&mut self) {
+        self.update_average_prices();
+
+        let mut price_totals: BTreeMap<ProductType, (f32, i32)> = BTreeMap::new();
+        for business in self.businesses.values() {
+            let (total, count) = price_totals.entry(business.product_type).or_default();
+            *total += business.product_price as f32;
+            *count += 1;
+        }
+
+        // Each business's price change, weighed by what was spent with it this month
+        let mut weighted_change = 0.;
+        let mut total_spending = 0.;
+        for business in self.businesses.values_mut() {
+            // The carried part of a price change counts, so that cheap products moving a whole step at once do
+            // not jolt the index
+            let spending = business.product_price as f64 * business.units_sold_this_month as f64;
+            let previous_price = business.product_price as f32 + business.price_change_carried;
+
+            // Competitors' average leaves the business's own price out
+            let (total, count) = price_totals[&business.product_type];
+            let competitor_price = match count {
+                1 => None,
+                count => Some((total - business.product_price as f32) / (count - 1) as f32),
+            };
+            business.adjust_price(competitor_price);
+
+            let price = business.product_price as f32 + business.price_change_carried;
+            weighted_change += spending * (price / previous_price) as f64;
+            total_spending += spending;
+        }
+
+        let price_change = match total_spending {
+            spending if spending > 0. => (weighted_change / spending) as f32,
+            _ => 1.,
+        };
+        self.update_consumer_price_index(price_change);
+        self.index_wages();
+    }
+
+    /// Average price paid for each product this month, including sales tax
+    fn update_average_prices(&mut self) {
+        let mut spending: BTreeMap<ProductType, (f64, i64)> = BTreeMap::new();
+        for business in self.businesses.values() {
+            let sales_tax_rate =
+                Business::get_sales_tax_rate(&self.rules.sales_tax_rule, business.product_type);
+            let units = business.units_sold_this_month as i64;
+
+            let (total, total_units) = spending.entry(business.product_type).or_default();
+            *total += business.get_shelf_price(sales_tax_rate) as f64 * units as f64;
+            *total_units += units;
+        }
+
+        for (product_type, (total, units)) in spending {
+            if units > 0 {
+                self.prices
+                    .average_prices
+                    .insert(product_type, (total / units as f64) as f32);
+            }
+        }
+    }
+
+    /// The index follows the businesses that sold something, so buyers moving to a cheaper seller or a seller closing
+    /// down does not move it
+    fn update_consumer_price_index(&mut self, price_change: f32) {
+        let prices = &mut self.prices;
+        if prices.recent_indexes.is_empty() {
+            prices.recent_indexes.push(prices.consumer_price_index);
+        }
+        prices.consumer_price_index *= price_change;
+
+        prices.recent_indexes.push(prices.consumer_price_index);
+        if prices.recent_indexes.len() > INDEX_HISTORY_MONTHS + 1 {
+            prices.recent_indexes.remove(0);
+        }
+
+        // Less than a year of history is stretched out to a yearly rate
+        let months = prices.recent_indexes.len() - 1;
+        prices.inflation = match months {
+            0 => 0.,
+            months => {
+                (prices.consumer_price_index / prices.recent_indexes[0])
+                    .powf(INDEX_HISTORY_MONTHS as f32 / months as f32)
+                    - 1.
+            }
+        };
+    }
+
+    /// Salaries and the cost of making products catch up with prices a little each month. Like pay rises, they are
+    /// never cut when prices fall
+    fn index_wages(&mut self) {
+        let wage_change = (self.prices.consumer_price_index / 100. / self.prices.wage_index)
+            .min(1. + MAXIMUM_MONTHLY_INDEXATION);
+        if wage_change <= 1. {
+            return;
+        }
+        self.prices.wage_index *= wage_change;
+
+        for business in self.businesses.values_mut() {
+            business.employee_salary = (business.employee_salary as f32 * wage_change) as i32;
+            business.production_cost_per_product *= wage_change;
+
+            for emp_id in &business.employees {
+                if let Some(emp) = self.people.get_mut(emp_id) {
+                    emp.set_salary(business.employee_salary);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn add_business(state: &mut GameState, product_price: i32, units_sold: i32) -> Uuid {
+        let business = Business {
+            id: Uuid::new_v4(),
+            product_price,
+            employee_salary: 30000,
+            units_sold_this_month: units_sold,
+            ..Business::default()
+        };
+        let id = business.id;
+        state.businesses.insert(id, business);
+
+        id
+    }
+
+    fn sell(state: &mut GameState, units_sold: i32) {
+        for business in state.businesses.values_mut() {
+            business.units_sold_this_month = units_sold;
+        }
+    }
+
+    #[test]
+    fn index_weighs_each_sellers_price_change_by_its_sales() {
+        let mut state = GameState::default();
+        let rising = add_business(&mut state, 100, 100);
+        let steady = add_business(&mut state, 200, 95);
+        state
+            .businesses
+            .get_mut(&steady)
+            .unwrap()
+            .units_missed_this_month = 5;
+        state.businesses.get_mut(&steady).unwrap().product_type = ProductType::Food;
+
+        state.update_prices();
+
+        // Customers bought everything they wanted, so the first raises its price by 2.5%, which is 2 with 0.5 carried
+        assert_eq!(state.businesses[&rising].product_price, 102);
+        assert_eq!(state.businesses[&steady].product_price, 200);
+        let expected = (10000. * 1.025 + 19000.) / 29000. * 100.;
+        assert!((state.prices.consumer_price_index - expected).abs() < 0.01);
+        assert!(state.prices.inflation > 0.);
+    }
+
+    #[test]
+    fn switching_sellers_does_not_move_the_index() {
+        let mut state = GameState::default();
+        let cheap = add_business(&mut state, 10, 95);
+        let dear = add_business(&mut state, 11, 0);
+        state
+            .businesses
+            .get_mut(&cheap)
+            .unwrap()
+            .units_missed_this_month = 5;
+        state.update_prices();
+
+        // Everyone buys from the dearer seller, then the cheaper one closes
+        state
+            .businesses
+            .get_mut(&dear)
+            .unwrap()
+            .units_sold_this_month = 95;
+        state
+            .businesses
+            .get_mut(&dear)
+            .unwrap()
+            .units_missed_this_month = 5;
+        state.update_prices();
+        state.businesses.remove(&cheap);
+        sell(&mut state, 95);
+        state
+            .businesses
+            .get_mut(&dear)
+            .unwrap()
+            .units_missed_this_month = 5;
+        state.update_prices();
+
+        assert_eq!(state.businesses[&dear].product_price, 11);
+        assert_eq!(state.prices.consumer_price_index, 100.);
+        assert_eq!(state.prices.inflation, 0.);
+    }
+
+    #[test]
+    fn wages_catch_up_with_prices_but_are_not_cut() {
+        let mut state = GameState::default();
+        let bid = add_business(&mut state, 100, 0);
+        state.prices.consumer_price_index = 110.;
+
+        state.update_prices();
+        assert!((state.prices.wage_index - 1.02).abs() < 0.0001);
+        assert_eq!(state.businesses[&bid].employee_salary, 30600);
+
+        for _ in 0..12 {
+            state.update_prices();
+        }
+        assert!((state.prices.wage_index - 1.1).abs() < 0.0001);
+        assert_eq!(state.businesses[&bid].employee_salary, 32999);
+
+        state.prices.consumer_price_index = 60.;
+        state.update_prices();
+        assert!((state.prices.wage_index - 1.1).abs() < 0.0001);
+        assert_eq!(state.businesses[&bid].employee_salary, 32999);
+    }
+
+    #[test]
+    fn prices_fall_when_customers_can_not_afford_them() {
+        let mut state = GameState::default();
+        let bid = add_business(&mut state, 100, 50);
+        state
+            .businesses
+            .get_mut(&bid)
+            .unwrap()
+            .units_missed_this_month = 50;
+
+        state.update_prices();
+
+        let business = &state.businesses[&bid];
+        assert_eq!(business.product_price, 95);
+        assert_eq!(business.units_sold_this_month, 0);
+        assert_eq!(business.units_missed_this_month, 0);
+    }
+}
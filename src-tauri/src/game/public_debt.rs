@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+use super::structs::{GameState, PublicDebtState};
+use crate::common::errors::{Error, IncResult};
+
+const BASE_INTEREST_RATE: f32 = 0.02; // Yearly rate lenders ask of the most trusted governments
+const MAXIMUM_DEBT_TO_INCOME: f32 = 2.5; // Lenders will not buy bonds that take the debt above this many years of income
+
+/// How much lenders trust the government to pay back what it owes
+#[allow(clippy::upper_case_acronyms)] // Ratings are written the way lenders write them
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CreditRating {
+    #[default]
+    AAA,
+    AA,
+    A,
+    BBB,
+    BB,
+    B,
+    CCC,
+}
+
+impl CreditRating {
+    /// Rating for debt worth this many years of government income
+    pub fn from_debt_to_income(debt_to_income: f32) -> Self {
+        match debt_to_income {
+            r if r < 0.3 => CreditRating::AAA,
+            r if r < 0.6 => CreditRating::AA,
+            r if r < 0.9 => CreditRating::A,
+            r if r < 1.2 => CreditRating::BBB,
+            r if r < 1.6 => CreditRating::BB,
+            r if r < 2. => CreditRating::B,
+            _ => CreditRating::CCC,
+        }
+    }
+
+    /// Yearly interest rate on bonds sold with this rating
+    pub fn get_interest_rate(self) -> f32 {
+        let risk_premium = match self {
+            CreditRating::AAA => 0.,
+            CreditRating::AA => 0.005,
+            CreditRating::A => 0.01,
+            CreditRating::BBB => 0.02,
+            CreditRating::BB => 0.035,
+            CreditRating::B => 0.05,
+            CreditRating::CCC => 0.08,
+        };
+
+        BASE_INTEREST_RATE + risk_premium
+    }
+}
+
+impl PublicDebtState {
+    pub fn get_monthly_interest(&self) -> i64 {
+        (self.owed as f64 * self.interest_rate as f64 / 12.) as i64
+    }
+}
+
+impl GameState {
+    /// Yearly income from taxes, based on what was collected or is expected this month
+    fn get_yearly_income(&self) -> i64 {
+        (self.finance_data.expected_person_income
+            + self.finance_data.expected_business_income
+            + self.finance_data.sales_tax_last_month)
+            * 12
+    }
+
+    fn get_debt_to_income(&self, owed: i64) -> f32 {
+        match self.get_yearly_income() {
+            income if income > 0 => owed as f32 / income as f32,
+            _ if owed > 0 => MAXIMUM_DEBT_TO_INCOME, // Any debt is too much without income
+            _ => 0.,
+        }
+    }
+
+    /// Most the government can borrow before lenders stop buying its bonds
+    pub fn get_borrowing_limit(&self) -> i64 {
+        let limit = (self.get_yearly_income() as f64 * MAXIMUM_DEBT_TO_INCOME as f64) as i64;
+        (limit - self.public_debt.owed).max(0)
+    }
+
+    /// Sells bonds, adding the amount to the government's balance
+    pub fn issue_bonds(&mut self, amount: i64) -> IncResult<()> {
+        if amount <= 0 {
+            return Err(Error::Warning(
+                "The amount to borrow must be more than 0.".to_string(),
+            ));
+        }
+
+        if amount > self.get_borrowing_limit() {
+            return Err(Error::Danger(
+                "Lenders will not buy any more bonds until the debt falls compared to income."
+                    .to_string(),
+            ));
+        }
+
+        self.public_debt.owed += amount;
+        self.public_debt.borrowed_last_month += amount;
+        self.government_balance += amount;
+        self.update_credit_rating();
+
+        Ok(())
+    }
+
+    /// Buys back bonds using the government's balance
+    pub fn repay_public_debt(&mut self, amount: i64) -> IncResult<()> {
+        if amount <= 0 {
+            return Err(Error::Warning(
+                "The amount to repay must be more than 0.".to_string(),
+            ));
+        }
+
+        if amount > self.government_balance {
+            return Err(Error::Danger(
+                "The government can not afford to repay this much.".to_string(),
+            ));
+        }
+
+        let amount = amount.min(self.public_debt.owed);
+        self.public_debt.owed -= amount;
+        self.public_debt.repaid_last_month += amount;
+        self.government_balance -= amount;
+        self.update_credit_rating();
+
+        Ok(())
+    }
+
+    /// Runs at the end of each month after the government's spending. Interest is paid on the debt,
+    /// then bonds are sold to cover as much of any deficit as lenders allow. Returns the interest paid
+    pub fn service_public_debt(&mut self) -> i64 {
+        let interest = self.public_debt.get_monthly_interest();
+        self.public_debt.interest_paid_last_month = interest;
+        self.government_balance -= interest;
+
+        if self.government_balance < 0 {
+            let amount = (-self.government_balance).min(self.get_borrowing_limit());
+            if amount > 0 {
+                self.public_debt.owed += amount;
+                self.public_debt.borrowed_last_month += amount;
+                self.government_balance += amount;
+            }
+        }
+
+        self.update_credit_rating();
+        interest
+    }
+
+    fn update_credit_rating(&mut self) {
+        let debt_to_income = self.get_debt_to_income(self.public_debt.owed);
+        let public_debt = &mut self.public_debt;
+
+        public_debt.debt_to_income = debt_to_income;
+        public_debt.credit_rating = CreditRating::from_debt_to_income(debt_to_income);
+        public_debt.interest_rate = public_debt.credit_rating.get_interest_rate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with_income(monthly_income: i64) -> GameState {
+        let mut state = GameState::default();
+        state.finance_data.expected_person_income = monthly_income;
+        state
+    }
+
+    #[test]
+    fn deficits_are_covered_by_bonds() {
+        let mut state = state_with_income(100000);
+        state.government_balance = -50000;
+
+        state.service_public_debt();
+
+        assert_eq!(state.government_balance, 0);
+        assert_eq!(state.public_debt.owed, 50000);
+        assert_eq!(state.public_debt.borrowed_last_month, 50000);
+    }
+
+    #[test]
+    fn rating_worsens_as_debt_grows() {
+        let mut state = state_with_income(100000);
+        state.issue_bonds(100000).unwrap();
+        let good_rating = state.public_debt.credit_rating;
+        let good_rate = state.public_debt.interest_rate;
+
+        state.issue_bonds(1500000).unwrap();
+
+        assert!(state.public_debt.credit_rating > good_rating);
+        assert!(state.public_debt.interest_rate > good_rate);
+        assert!(state.public_debt.get_monthly_interest() > 0);
+    }
+
+    #[test]
+    fn lenders_stop_at_the_borrowing_limit() {
+        let mut state = state_with_income(100000);
+        let limit = state.get_borrowing_limit();
+
+        assert!(state.issue_bonds(limit + 1).is_err());
+        state.issue_bonds(limit).unwrap();
+
+        state.government_balance = -1000;
+        state.service_public_debt();
+        assert!(state.government_balance < 0);
+
+        state.government_balance = 10000;
+        state.repay_public_debt(10000).unwrap();
+        assert_eq!(state.public_debt.owed, limit - 10000);
+        assert!(state.repay_public_debt(1).is_err());
+    }
+}
@@ -0,0 +1,1108 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::{
+    events::{json_get_f64, json_get_i64, App},
+    structs::{
+        BusinessFundingRule, BusinessTaxRule, CoverFoodRule, CoverFoodUnemployedRule, DenyAgeRule,
+        DenyHealthPercentageRule, GameState, GameStateRules, HospitalChargesRule,
+        HousingSubsidyRule, InheritanceTaxRule, MinimumWageRule, PensionRule, QuarantineRule,
+        RentCapRule, SalesTaxRule, TaxRule, TuitionRule,
+    },
+};
+use crate::common::errors::{Error, IncResult};
+
+const FOOD_COVER_COST: i64 = 4; // Daily cost of covering one person's food
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterKind {
+    Integer,
+    Decimal,
+}
+
+/// A value the player sets when updating a rule
+#[derive(Serialize)]
+pub struct RuleParameter {
+    pub key: &'static str,
+    pub kind: ParameterKind,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+}
+
+impl RuleParameter {
+    const fn integer(key: &'static str) -> Self {
+        Self {
+            key,
+            kind: ParameterKind::Integer,
+            minimum: Some(0.),
+            maximum: None,
+        }
+    }
+
+    const fn decimal(key: &'static str) -> Self {
+        Self {
+            key,
+            kind: ParameterKind::Decimal,
+            minimum: Some(0.),
+            maximum: None,
+        }
+    }
+
+    const fn rate(key: &'static str) -> Self {
+        Self {
+            key,
+            kind: ParameterKind::Decimal,
+            minimum: Some(0.),
+            maximum: Some(1.),
+        }
+    }
+
+    fn validate(&self, data: &Value) -> IncResult<()> {
+        let value = match self.kind {
+            ParameterKind::Integer => json_get_i64(data, self.key)? as f64,
+            ParameterKind::Decimal => json_get_f64(data, self.key)?,
+        };
+
+        let below_minimum = self.minimum.is_some_and(|minimum| value < minimum);
+        let above_maximum = self.maximum.is_some_and(|maximum| value > maximum);
+
+        if below_minimum || above_maximum {
+            return Err(Error::Danger(format!(
+                "'{}' is out of range for this rule.",
+                self.key
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The government budget a rule is paid from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleBudget {
+    Business,
+    Welfare,
+}
+
+/// A policy the player can enable, disable and configure.
+/// The simulation reads the rule's settings from `GameStateRules` wherever the rule has an effect
+pub trait Rule {
+    fn id(&self) -> i32;
+    fn name(&self) -> &'static str;
+    fn app(&self) -> App;
+    /// How the rule changes the simulation when it is enabled
+    fn description(&self) -> &'static str;
+    fn parameters(&self) -> &'static [RuleParameter];
+
+    fn enabled(&self) -> bool;
+    fn set_enabled(&mut self, enabled: bool);
+
+    fn budget(&self) -> Option<RuleBudget> {
+        None
+    }
+
+    /// Monthly cost of the rule with its current settings
+    fn budget_cost(&self) -> i64 {
+        0
+    }
+
+    /// Monthly cost the rule would have with the given parameters
+    fn cost_of(&self, _data: &Value) -> IncResult<i64> {
+        Ok(0)
+    }
+
+    /// Applies parameters that have already been validated against the schema
+    fn update(&mut self, data: &Value) -> IncResult<()>;
+
+    fn settings(&self) -> IncResult<Value>;
+}
+
+impl Rule for TaxRule {
+    fn id(&self) -> i32 {
+        0
+    }
+
+    fn name(&self) -> &'static str {
+        "Tax"
+    }
+
+    fn app(&self) -> App {
+        App::Finance
+    }
+
+    fn description(&self) -> &'static str {
+        "People earning at least the minimum salary pay the rule's rate instead of the standard rate, up to the first tax bracket."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 2] = [
+            RuleParameter::integer("minimum_salary"),
+            RuleParameter::rate("tax_rate"),
+        ];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.minimum_salary = json_get_i64(data, "minimum_salary")? as i32;
+        self.tax_rate = json_get_f64(data, "tax_rate")? as f32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for BusinessTaxRule {
+    fn id(&self) -> i32 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "Business Tax"
+    }
+
+    fn app(&self) -> App {
+        App::Finance
+    }
+
+    fn description(&self) -> &'static str {
+        "Businesses with at least the minimum monthly income are taxed at the rule's rate instead of the standard business rate."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 2] = [
+            RuleParameter::decimal("minimum_monthly_income"),
+            RuleParameter::rate("tax_rate"),
+        ];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.minimum_monthly_income = json_get_f64(data, "minimum_monthly_income")?;
+        self.tax_rate = json_get_f64(data, "tax_rate")? as f32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for BusinessFundingRule {
+    fn id(&self) -> i32 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "Business Funding"
+    }
+
+    fn app(&self) -> App {
+        App::Business
+    }
+
+    fn description(&self) -> &'static str {
+        "Up to the business count, businesses earning less than the maximum income are given the fund each month. Places left over bail out businesses that would otherwise go bankrupt."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 3] = [
+            RuleParameter::integer("fund"),
+            RuleParameter::integer("maximum_income"),
+            RuleParameter::integer("business_count"),
+        ];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn budget(&self) -> Option<RuleBudget> {
+        Some(RuleBudget::Business)
+    }
+
+    fn budget_cost(&self) -> i64 {
+        self.budget_cost
+    }
+
+    fn cost_of(&self, data: &Value) -> IncResult<i64> {
+        Ok(json_get_i64(data, "fund")? * json_get_i64(data, "business_count")?)
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.fund = json_get_i64(data, "fund")?;
+        self.maximum_income = json_get_i64(data, "maximum_income")?;
+        self.business_count = json_get_i64(data, "business_count")? as i32;
+        self.budget_cost = self.cost_of(data)?;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for DenyAgeRule {
+    fn id(&self) -> i32 {
+        3
+    }
+
+    fn name(&self) -> &'static str {
+        "Healthcare Deny Age"
+    }
+
+    fn app(&self) -> App {
+        App::Healthcare
+    }
+
+    fn description(&self) -> &'static str {
+        "People older than the maximum age are not admitted to hospital."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 1] = [RuleParameter::integer("maximum_age")];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.maximum_age = json_get_i64(data, "maximum_age")? as i32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for DenyHealthPercentageRule {
+    fn id(&self) -> i32 {
+        4
+    }
+
+    fn name(&self) -> &'static str {
+        "Deny Health Percentage"
+    }
+
+    fn app(&self) -> App {
+        App::Healthcare
+    }
+
+    fn description(&self) -> &'static str {
+        "People whose health is above the maximum percentage are not admitted to hospital."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 1] = [RuleParameter {
+            key: "maximum_percentage",
+            kind: ParameterKind::Integer,
+            minimum: Some(0.),
+            maximum: Some(100.),
+        }];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.maximum_percentage = json_get_i64(data, "maximum_percentage")? as i32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for CoverFoodRule {
+    fn id(&self) -> i32 {
+        5
+    }
+
+    fn name(&self) -> &'static str {
+        "Cover Food"
+    }
+
+    fn app(&self) -> App {
+        App::Welfare
+    }
+
+    fn description(&self) -> &'static str {
+        "Up to the people count, the government pays for the food of people earning less than the maximum salary."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 2] = [
+            RuleParameter::integer("people_count"),
+            RuleParameter::integer("maximum_salary"),
+        ];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn budget(&self) -> Option<RuleBudget> {
+        Some(RuleBudget::Welfare)
+    }
+
+    fn budget_cost(&self) -> i64 {
+        self.budget_cost
+    }
+
+    fn cost_of(&self, data: &Value) -> IncResult<i64> {
+        Ok(json_get_i64(data, "people_count")? * FOOD_COVER_COST)
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.people_count = json_get_i64(data, "people_count")? as i32;
+        self.maximum_salary = json_get_i64(data, "maximum_salary")? as i32;
+        self.budget_cost = self.cost_of(data)?;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for CoverFoodUnemployedRule {
+    fn id(&self) -> i32 {
+        6
+    }
+
+    fn name(&self) -> &'static str {
+        "Cover Food Unemployed"
+    }
+
+    fn app(&self) -> App {
+        App::Welfare
+    }
+
+    fn description(&self) -> &'static str {
+        "Up to the people count, the government pays for the food of unemployed people."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 1] = [RuleParameter::integer("people_count")];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn budget(&self) -> Option<RuleBudget> {
+        Some(RuleBudget::Welfare)
+    }
+
+    fn budget_cost(&self) -> i64 {
+        self.budget_cost
+    }
+
+    fn cost_of(&self, data: &Value) -> IncResult<i64> {
+        Ok(json_get_i64(data, "people_count")? * FOOD_COVER_COST)
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.people_count = json_get_i64(data, "people_count")? as i32;
+        self.budget_cost = self.cost_of(data)?;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for RentCapRule {
+    fn id(&self) -> i32 {
+        7
+    }
+
+    fn name(&self) -> &'static str {
+        "Rent Cap"
+    }
+
+    fn app(&self) -> App {
+        App::Welfare
+    }
+
+    fn description(&self) -> &'static str {
+        "No renter pays more than the maximum rent, fewer homes are built while the cap is below market rent."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 1] = [RuleParameter::integer("maximum_rent")];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.maximum_rent = json_get_i64(data, "maximum_rent")? as i32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for HousingSubsidyRule {
+    fn id(&self) -> i32 {
+        8
+    }
+
+    fn name(&self) -> &'static str {
+        "Housing Subsidy"
+    }
+
+    fn app(&self) -> App {
+        App::Welfare
+    }
+
+    fn description(&self) -> &'static str {
+        "Up to the people count, renters earning less than the maximum salary have the subsidy paid towards their rent each month."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 3] = [
+            RuleParameter::integer("people_count"),
+            RuleParameter::integer("subsidy"),
+            RuleParameter::integer("maximum_salary"),
+        ];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn budget(&self) -> Option<RuleBudget> {
+        Some(RuleBudget::Welfare)
+    }
+
+    fn budget_cost(&self) -> i64 {
+        self.budget_cost
+    }
+
+    fn cost_of(&self, data: &Value) -> IncResult<i64> {
+        Ok(json_get_i64(data, "people_count")? * json_get_i64(data, "subsidy")?)
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.people_count = json_get_i64(data, "people_count")? as i32;
+        self.subsidy = json_get_i64(data, "subsidy")? as i32;
+        self.maximum_salary = json_get_i64(data, "maximum_salary")? as i32;
+        self.budget_cost = self.cost_of(data)?;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for InheritanceTaxRule {
+    fn id(&self) -> i32 {
+        9
+    }
+
+    fn name(&self) -> &'static str {
+        "Inheritance Tax"
+    }
+
+    fn app(&self) -> App {
+        App::Finance
+    }
+
+    fn description(&self) -> &'static str {
+        "When someone dies, the part of their estate above the minimum is taxed at the rule's rate before it is passed on."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 2] = [
+            RuleParameter::integer("minimum_estate"),
+            RuleParameter::rate("tax_rate"),
+        ];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.minimum_estate = json_get_i64(data, "minimum_estate")?;
+        self.tax_rate = json_get_f64(data, "tax_rate")? as f32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for TuitionRule {
+    fn id(&self) -> i32 {
+        10
+    }
+
+    fn name(&self) -> &'static str {
+        "Tuition Coverage"
+    }
+
+    fn app(&self) -> App {
+        App::Welfare
+    }
+
+    fn description(&self) -> &'static str {
+        "The government pays this share of every new student's tuition, so more people go on to higher education and they leave with less debt."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 1] = [RuleParameter::rate("coverage")];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.coverage = json_get_f64(data, "coverage")? as f32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for PensionRule {
+    fn id(&self) -> i32 {
+        11
+    }
+
+    fn name(&self) -> &'static str {
+        "Pension Policy"
+    }
+
+    fn app(&self) -> App {
+        App::Welfare
+    }
+
+    fn description(&self) -> &'static str {
+        "People retire at the rule's retirement age instead of the usual one. Retirees with savings above the maximum balance are not paid their pension, a maximum balance of 0 pays everyone."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 2] = [
+            RuleParameter {
+                key: "retirement_age",
+                kind: ParameterKind::Integer,
+                minimum: Some(50.),
+                maximum: Some(80.),
+            },
+            RuleParameter::integer("maximum_balance"),
+        ];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.retirement_age = json_get_i64(data, "retirement_age")? as i32;
+        self.maximum_balance = json_get_i64(data, "maximum_balance")?;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for HospitalChargesRule {
+    fn id(&self) -> i32 {
+        12
+    }
+
+    fn name(&self) -> &'static str {
+        "Hospital Charges"
+    }
+
+    fn app(&self) -> App {
+        App::Healthcare
+    }
+
+    fn description(&self) -> &'static str {
+        "Patients are billed for every day they spend in hospital, at what their bed costs to run. The government pays this share of each bill, and anything a patient can not pay becomes medical debt."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 1] = [RuleParameter::rate("coverage")];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.coverage = json_get_f64(data, "coverage")? as f32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for QuarantineRule {
+    fn id(&self) -> i32 {
+        13
+    }
+
+    fn name(&self) -> &'static str {
+        "Quarantine"
+    }
+
+    fn app(&self) -> App {
+        App::Healthcare
+    }
+
+    fn description(&self) -> &'static str {
+        "Everyone is told to stay at home while at least the minimum number of people are infected. Disease spreads far more slowly, but people are less happy while the quarantine lasts."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 1] = [RuleParameter::integer("minimum_infected")];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.minimum_infected = json_get_i64(data, "minimum_infected")? as i32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for SalesTaxRule {
+    fn id(&self) -> i32 {
+        14
+    }
+
+    fn name(&self) -> &'static str {
+        "Sales Tax"
+    }
+
+    fn app(&self) -> App {
+        App::Finance
+    }
+
+    fn description(&self) -> &'static str {
+        "Every purchase is taxed at its product's rate, paid by the customer on top of the price. Housing is taxed on rent. Higher prices mean people can afford fewer of the products they want."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 5] = [
+            RuleParameter::rate("leisure"),
+            RuleParameter::rate("food"),
+            RuleParameter::rate("housing"),
+            RuleParameter::rate("furniture"),
+            RuleParameter::rate("healthcare_goods"),
+        ];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.leisure = json_get_f64(data, "leisure")? as f32;
+        self.food = json_get_f64(data, "food")? as f32;
+        self.housing = json_get_f64(data, "housing")? as f32;
+        self.furniture = json_get_f64(data, "furniture")? as f32;
+        self.healthcare_goods = json_get_f64(data, "healthcare_goods")? as f32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl Rule for MinimumWageRule {
+    fn id(&self) -> i32 {
+        15
+    }
+
+    fn name(&self) -> &'static str {
+        "Minimum Wage"
+    }
+
+    fn app(&self) -> App {
+        App::Business
+    }
+
+    fn description(&self) -> &'static str {
+        "Businesses must pay every employee at least the minimum wage each year. Those that can not afford the raise let staff go, and some may go bankrupt."
+    }
+
+    fn parameters(&self) -> &'static [RuleParameter] {
+        const PARAMETERS: [RuleParameter; 1] = [RuleParameter::integer("minimum_wage")];
+        &PARAMETERS
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn update(&mut self, data: &Value) -> IncResult<()> {
+        self.minimum_wage = json_get_i64(data, "minimum_wage")? as i32;
+        Ok(())
+    }
+
+    fn settings(&self) -> IncResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+fn unknown_rule(id: i32) -> Error {
+    Error::Warning(format!("There is no rule with id {}.", id))
+}
+
+impl GameStateRules {
+    /// Every rule the player can use, in id order
+    pub fn all(&self) -> [&dyn Rule; 16] {
+        [
+            &self.tax_rule,
+            &self.business_tax_rule,
+            &self.business_funding_rule,
+            &self.deny_age_rule,
+            &self.deny_health_percentage_rule,
+            &self.cover_food_rule,
+            &self.cover_food_unemployed_rule,
+            &self.rent_cap_rule,
+            &self.housing_subsidy_rule,
+            &self.inheritance_tax_rule,
+            &self.tuition_rule,
+            &self.pension_rule,
+            &self.hospital_charges_rule,
+            &self.quarantine_rule,
+            &self.sales_tax_rule,
+            &self.minimum_wage_rule,
+        ]
+    }
+
+    fn all_mut(&mut self) -> [&mut dyn Rule; 16] {
+        [
+            &mut self.tax_rule,
+            &mut self.business_tax_rule,
+            &mut self.business_funding_rule,
+            &mut self.deny_age_rule,
+            &mut self.deny_health_percentage_rule,
+            &mut self.cover_food_rule,
+            &mut self.cover_food_unemployed_rule,
+            &mut self.rent_cap_rule,
+            &mut self.housing_subsidy_rule,
+            &mut self.inheritance_tax_rule,
+            &mut self.tuition_rule,
+            &mut self.pension_rule,
+            &mut self.hospital_charges_rule,
+            &mut self.quarantine_rule,
+            &mut self.sales_tax_rule,
+            &mut self.minimum_wage_rule,
+        ]
+    }
+
+    pub fn get(&self, id: i32) -> IncResult<&dyn Rule> {
+        self.all()
+            .into_iter()
+            .find(|rule| rule.id() == id)
+            .ok_or_else(|| unknown_rule(id))
+    }
+
+    pub fn get_mut(&mut self, id: i32) -> IncResult<&mut dyn Rule> {
+        self.all_mut()
+            .into_iter()
+            .find(|rule| rule.id() == id)
+            .ok_or_else(|| unknown_rule(id))
+    }
+
+    /// What the rules paid from the budget cost each month, leaving out the rule with the given id
+    fn used_budget(&self, budget: RuleBudget, except_id: i32) -> i64 {
+        self.all()
+            .iter()
+            .filter(|rule| rule.id() != except_id && rule.budget() == Some(budget))
+            .map(|rule| rule.budget_cost())
+            .sum()
+    }
+}
+
+impl GameState {
+    fn get_rule_budget(&self, budget: RuleBudget) -> i64 {
+        match budget {
+            RuleBudget::Business => self.business_budget,
+            RuleBudget::Welfare => self.welfare_budget,
+        }
+    }
+
+    pub fn set_rule_enabled(&mut self, id: i32, enabled: bool) -> IncResult<()> {
+        self.rules.get_mut(id)?.set_enabled(enabled);
+        Ok(())
+    }
+
+    /// Validates the parameters against the rule's schema and budget before applying them.
+    /// Rules paid from a budget return their new cost
+    pub fn update_rule(&mut self, id: i32, data: &Value) -> IncResult<Value> {
+        let rule = self.rules.get(id)?;
+
+        for parameter in rule.parameters() {
+            parameter.validate(data)?;
+        }
+
+        if let Some(budget) = rule.budget() {
+            let remaining_budget =
+                self.get_rule_budget(budget) - self.rules.used_budget(budget, id);
+
+            if rule.cost_of(data)? > remaining_budget {
+                return Err(Error::Danger(format!(
+                    "{} costs more than is left in the budget.",
+                    rule.name()
+                )));
+            }
+        }
+
+        let rule = self.rules.get_mut(id)?;
+        rule.update(data)?;
+
+        if rule.budget().is_some() {
+            return Ok(json!({
+                "budget_cost": rule.budget_cost(),
+            }));
+        }
+
+        Ok(json!({}))
+    }
+
+    /// Describes every rule so the frontend can show them without knowing about each one
+    pub fn list_rules(&self) -> IncResult<Vec<RuleInfo>> {
+        self.rules
+            .all()
+            .iter()
+            .map(|rule| {
+                Ok(RuleInfo {
+                    id: rule.id(),
+                    name: rule.name(),
+                    app_id: rule.app() as u8,
+                    description: rule.description(),
+                    parameters: rule.parameters(),
+                    budget: rule.budget(),
+                    budget_cost: rule.budget_cost(),
+                    enabled: rule.enabled(),
+                    settings: rule.settings()?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+pub struct RuleInfo {
+    pub id: i32,
+    pub name: &'static str,
+    pub app_id: u8,
+    pub description: &'static str,
+    pub parameters: &'static [RuleParameter],
+    pub budget: Option<RuleBudget>,
+    pub budget_cost: i64,
+    pub enabled: bool,
+    pub settings: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::business::{Business, ProductType};
+
+    #[test]
+    fn rule_ids_match_their_position() {
+        let rules = GameStateRules::default();
+
+        for (idx, rule) in rules.all().iter().enumerate() {
+            assert_eq!(rule.id(), idx as i32, "{}", rule.name());
+        }
+    }
+
+    #[test]
+    fn unknown_rules_are_an_error() {
+        let mut state = GameState::default();
+
+        assert!(state.set_rule_enabled(100, true).is_err());
+        assert!(state.update_rule(-1, &json!({})).is_err());
+    }
+
+    #[test]
+    fn welfare_rules_share_the_welfare_budget() {
+        let mut state = GameState {
+            welfare_budget: 100,
+            ..GameState::default()
+        };
+
+        let res = state
+            .update_rule(5, &json!({ "people_count": 20, "maximum_salary": 20000 }))
+            .unwrap();
+        assert_eq!(res, json!({ "budget_cost": 80 }));
+
+        // Only 20 is left of the welfare budget
+        let subsidy = json!({ "people_count": 3, "subsidy": 10, "maximum_salary": 20000 });
+        assert!(state.update_rule(8, &subsidy).is_err());
+        assert_eq!(state.rules.housing_subsidy_rule.budget_cost, 0);
+    }
+
+    #[test]
+    fn parameters_are_checked_against_the_schema() {
+        let mut state = GameState::default();
+
+        let out_of_range = json!({ "minimum_salary": 10000, "tax_rate": 1.5 });
+        assert!(state.update_rule(0, &out_of_range).is_err());
+
+        let missing = json!({ "minimum_salary": 10000 });
+        assert!(state.update_rule(0, &missing).is_err());
+    }
+
+    #[test]
+    fn retirement_age_stays_within_a_working_life() {
+        let mut state = GameState::default();
+
+        for retirement_age in [0, 49, 81, i64::from(i32::MAX) + 1] {
+            let pension = json!({ "retirement_age": retirement_age, "maximum_balance": 0 });
+            assert!(state.update_rule(11, &pension).is_err());
+        }
+
+        let pension = json!({ "retirement_age": 67, "maximum_balance": 0 });
+        state.update_rule(11, &pension).unwrap();
+        assert_eq!(state.rules.pension_rule.retirement_age, 67);
+    }
+
+    #[test]
+    fn sales_tax_is_only_charged_while_enabled() {
+        let mut state = GameState::default();
+        let rates = json!({
+            "leisure": 0.2,
+            "food": 0.,
+            "housing": 0.05,
+            "furniture": 0.2,
+            "healthcare_goods": 0.,
+        });
+        state.update_rule(14, &rates).unwrap();
+
+        let leisure_rate = |state: &GameState| {
+            Business::get_sales_tax_rate(&state.rules.sales_tax_rule, ProductType::Leisure)
+        };
+        assert_eq!(leisure_rate(&state), 0.);
+
+        state.set_rule_enabled(14, true).unwrap();
+        assert_eq!(leisure_rate(&state), 0.2);
+        assert_eq!(
+            Business::get_sales_tax_rate(&state.rules.sales_tax_rule, ProductType::Food),
+            0.
+        );
+    }
+}
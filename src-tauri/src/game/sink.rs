@@ -0,0 +1,222 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::sync::Mutex;
+#[cfg(feature = "desktop")]
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use super::events::{App, AppUpdateType};
+use crate::{
+    common::{
+        errors::{Error, IncResult},
+        payloads::PayloadNewDay,
+    },
+    entities::business::ProductType,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnemploymentSeverity {
+    Mild,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BankruptcyReason {
+    NoBailout,    // Business funding was disabled or already given to other businesses
+    DebtTooLarge, // The fund would not have paid off the debt
+}
+
+/// Everything the simulation tells the frontend about
+#[derive(Debug)]
+pub enum GameEvent {
+    NewDay(PayloadNewDay),
+    LoadingStatus {
+        stage: &'static str,
+        steps: &'static [&'static str],
+    },
+    AppUpdate {
+        app: App,
+        update_type: AppUpdateType,
+        data: Value,
+    },
+    UnemployedHigh {
+        unemployed_count: i32,
+        percent: f32,
+        severity: UnemploymentSeverity,
+    },
+    BusinessBankrupt {
+        business_id: Uuid,
+        product_type: ProductType,
+        laid_off: usize,
+        reason: BankruptcyReason,
+    },
+    OutbreakStarted {
+        infected: i32,
+    },
+    Error(Error),
+    GameGenerated,
+    OpenDebuggerApp, // Only in debug mode
+    ShutdownScheduled {
+        seconds: u64,
+    },
+    GameShutdown,
+}
+
+impl GameEvent {
+    /// Name of the event the frontend listens for
+    pub const fn name(&self) -> &'static str {
+        match self {
+            GameEvent::NewDay(_) => "new_day",
+            GameEvent::LoadingStatus { .. } => "loading_status",
+            GameEvent::AppUpdate { .. } => "update_app",
+            GameEvent::UnemployedHigh { .. } => "unemployed_high",
+            GameEvent::BusinessBankrupt { .. } => "business_bankrupt",
+            GameEvent::OutbreakStarted { .. } => "outbreak_started",
+            GameEvent::Error(_) => "error",
+            GameEvent::GameGenerated => "game_generated",
+            GameEvent::OpenDebuggerApp => "open_debugger_app",
+            GameEvent::ShutdownScheduled { .. } => "shutdown_scheduled",
+            GameEvent::GameShutdown => "game_shutdown",
+        }
+    }
+
+    pub fn payload(&self) -> IncResult<Value> {
+        Ok(match self {
+            GameEvent::NewDay(payload) => serde_json::to_value(payload)?,
+            GameEvent::LoadingStatus { stage, steps } => {
+                let mut status = Map::new();
+                status.insert(stage.to_string(), json!(steps));
+                Value::Object(status)
+            }
+            GameEvent::AppUpdate {
+                app,
+                update_type,
+                data,
+            } => {
+                json!({ "app_id": *app as u8, "data": data, "update_type": update_type })
+            }
+            GameEvent::UnemployedHigh {
+                unemployed_count,
+                percent,
+                severity,
+            } => {
+                json!({ "unemployed_count": unemployed_count, "percent": percent, "severity": severity })
+            }
+            GameEvent::BusinessBankrupt {
+                business_id,
+                product_type,
+                laid_off,
+                reason,
+            } => {
+                json!({ "business_id": business_id, "product_type": product_type, "laid_off": laid_off, "reason": reason })
+            }
+            GameEvent::OutbreakStarted { infected } => json!({ "infected": infected }),
+            GameEvent::Error(err) => serde_json::to_value(err)?,
+            GameEvent::ShutdownScheduled { seconds } => json!({ "seconds": seconds }),
+            GameEvent::GameGenerated | GameEvent::OpenDebuggerApp | GameEvent::GameShutdown => {
+                Value::Null
+            }
+        })
+    }
+}
+
+/// Receives every event the simulation emits, so the game can run with or without the desktop app
+///
+/// Sinks are held across the game loop's awaits, so they must be shareable between threads
+pub trait GameEventSink: Send + Sync {
+    fn emit(&self, event: GameEvent) -> IncResult<()>;
+}
+
+#[cfg(feature = "desktop")]
+impl GameEventSink for AppHandle {
+    fn emit(&self, event: GameEvent) -> IncResult<()> {
+        self.emit_all(event.name(), event.payload()?)?;
+        Ok(())
+    }
+}
+
+/// Drops every event, for when there is no window to update
+pub struct NoopEventSink;
+
+impl GameEventSink for NoopEventSink {
+    fn emit(&self, _event: GameEvent) -> IncResult<()> {
+        Ok(())
+    }
+}
+
+/// Keeps every event in the order it was emitted, so tests can check what the frontend would have received
+#[derive(Default)]
+pub struct RecordingEventSink {
+    events: Mutex<Vec<GameEvent>>,
+}
+
+impl RecordingEventSink {
+    /// Returns the events recorded so far and clears them
+    pub fn take_events(&self) -> Vec<GameEvent> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+impl GameEventSink for RecordingEventSink {
+    fn emit(&self, event: GameEvent) -> IncResult<()> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::config::load_config,
+        game::{generation::generate_game, state_manager::GameStateSafe, structs::GameState},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn payloads_keep_the_frontend_format() {
+        let loading = GameEvent::LoadingStatus {
+            stage: "Generating people",
+            steps: &["Generating ages"],
+        };
+        assert_eq!(
+            loading.payload().unwrap(),
+            json!({ "Generating people": ["Generating ages"] })
+        );
+
+        let update = GameEvent::AppUpdate {
+            app: App::Welfare,
+            update_type: AppUpdateType::Month,
+            data: json!({}),
+        };
+        assert_eq!(
+            update.payload().unwrap(),
+            json!({ "app_id": 3, "data": {}, "update_type": "month" })
+        );
+    }
+
+    #[test]
+    fn records_generation_events_in_order() {
+        let mut config = load_config().unwrap();
+        config.starting_population = 200;
+
+        let state_mux: GameStateSafe = Arc::new(Mutex::new(GameState::default()));
+        let sink = RecordingEventSink::default();
+
+        generate_game(&state_mux, &config, &sink, 1).unwrap();
+
+        let stages: Vec<&str> = sink
+            .take_events()
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::LoadingStatus { stage, .. } => Some(*stage),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(stages, ["Generating people", "Generating businesses"]);
+        assert!(sink.take_events().is_empty());
+    }
+}
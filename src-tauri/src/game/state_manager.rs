@@ -1,49 +1,60 @@
 use super::{
+    epidemic::VACCINE_COST,
     events::{json_get_i64, update_app, App, AppUpdateType},
-    structs::{BusinessData, FinanceData, GameState, GameStateRules, HealthcareState},
+    sink::{GameEvent, GameEventSink, UnemploymentSeverity},
+    structs::{
+        BusinessData, EducationState, EpidemicState, FinanceData, GameState, GameStateRules,
+        HealthcareState, HouseholdDebtState, HousingState, IncomeTaxState, LabourMarketState,
+        PensionState, PriceState, PublicDebtState,
+    },
 };
 use crate::{
     as_decimal_percent,
     common::{
         config::Config,
         errors::{Error, IncResult},
-        util::{chance_one_in, generate_unemployed_salary, get_healthcare_group, Date, SlotArray},
+        util::{generate_unemployed_salary, get_healthcare_group, Date, GameRng, SlotArray},
     },
     entities::{
-        business::{Business, ProductType},
+        business::{Business, ProductType, FOUNDER_START_CAPITAL},
         person::{
-            debt::{Debt, DebtType},
+            education::{
+                EXPECTED_SPENDING_PER_PUPIL, MAXIMUM_EDUCATION_FUNDING, MINIMUM_EDUCATION_FUNDING,
+                SCHOOL_STARTING_AGE,
+            },
             person::{Job, Person},
         },
     },
-    game::events::{
-        get_monthly_data,
-        get_daily_data
-    }
+    game::events::{get_daily_data, get_monthly_data},
 };
+use rand::SeedableRng;
 use serde_json::json;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     sync::{Arc, Mutex},
 };
-use tauri::{AppHandle, Manager};
 use uuid::Uuid;
 
 const GOVERNMENT_START_BALANCE: u32 = 140000000;
-const THREE_YEAR_DAYS: usize = 1080; // days in three game years
-const EMPTY_DATA: i64 = -1;
+pub const THREE_YEAR_DAYS: usize = 1080; // days in three game years
+pub const EMPTY_DATA: i64 = -1;
 
 pub type GameStateSafe = Arc<Mutex<GameState>>;
 
 impl Default for GameState {
     fn default() -> Self {
         Self {
+            name: String::new(),
             tax_rate: 0.24,          // 24% default
             business_tax_rate: 0.22, // 22% default - TODO: emit warning if the tax is raised above 30% - this is the maximum tax rate businesses will tolerate
-            businesses: HashMap::new(),
-            people: HashMap::new(),
+            businesses: BTreeMap::new(),
+            people: BTreeMap::new(),
+            households: BTreeMap::new(),
             date: Date::default(),
 
+            seed: 0,
+            rng: GameRng::seed_from_u64(0),
+
             government_balance: GOVERNMENT_START_BALANCE as i64,
 
             births_in_last_month: SlotArray::new(30),
@@ -55,7 +66,16 @@ impl Default for GameState {
             rules: GameStateRules::default(),
             open_apps: HashMap::new(),
             healthcare: HealthcareState::default(),
-
+            housing: HousingState::default(),
+            education: EducationState::default(),
+            pensions: PensionState::default(),
+            household_debt: HouseholdDebtState::default(),
+            epidemic: EpidemicState::default(),
+            labour_market: LabourMarketState::default(),
+
+            income_tax: IncomeTaxState::default(),
+            public_debt: PublicDebtState::default(),
+            prices: PriceState::default(),
             finance_data: FinanceData::default(),
             welfare_budget: 0,
             welfare_owed: 0,
@@ -74,25 +94,36 @@ impl Default for GameState {
             expected_balance: 0,
 
             // Daily updates
-
             population_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
             births_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
             deaths_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
             life_expectancy_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
             hospital_usage_capacity_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
             average_welfare_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
-            average_unemployed_welfare_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
+            average_unemployed_welfare_graph_data: SlotArray::new_default(
+                THREE_YEAR_DAYS,
+                EMPTY_DATA,
+            ),
             government_balance_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
-            government_balance_prediction_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
+            government_balance_prediction_graph_data: SlotArray::new_default(
+                THREE_YEAR_DAYS,
+                EMPTY_DATA,
+            ),
+            infected_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
+            inflation_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
 
             // Monthly updates
-
             average_monthly_income_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
             government_losses_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
             business_count_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
             average_employees_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
-            business_average_monthly_income_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
+            business_average_monthly_income_graph_data: SlotArray::new_default(
+                THREE_YEAR_DAYS,
+                EMPTY_DATA,
+            ),
             unemployed_count_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
+            household_debt_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
+            public_debt_graph_data: SlotArray::new_default(THREE_YEAR_DAYS, EMPTY_DATA),
         }
     }
 }
@@ -114,6 +145,113 @@ impl GameState {
         }
     }
 
+    /// The founder leaves their old job and puts part of their balance into a new business,
+    /// from then on they are paid by drawing from it
+    pub fn found_business(
+        &mut self,
+        founder_id: Uuid,
+        product_type: ProductType,
+        config: &Config,
+    ) -> IncResult<Uuid> {
+        let founder = self
+            .people
+            .get(&founder_id)
+            .ok_or(Error::DangerUnexpected)?
+            .clone();
+        let start_capital = founder.balance * FOUNDER_START_CAPITAL;
+
+        let mut business = Business::default();
+        business.generate_midgame(
+            product_type,
+            config,
+            start_capital as f64,
+            self.prices.wage_index,
+            &mut self.rng,
+        );
+        business.owner_id = founder_id;
+
+        self.resign_if_employed(founder);
+
+        let founder = self
+            .people
+            .get_mut(&founder_id)
+            .ok_or(Error::DangerUnexpected)?;
+        founder.balance -= start_capital;
+        founder.job = Job::BusinessOwner(business.id);
+        founder.set_salary((business.get_owner_draw() * 12.) as i32);
+
+        let bid = business.id;
+        self.businesses.insert(bid, business);
+
+        Ok(bid)
+    }
+
+    /// Pays each retiree their pension, cutting every pension by the same share when they would
+    /// cost more than the pension budget. Returns what was paid
+    pub fn pay_pensions(&mut self) -> i64 {
+        let rule = &self.rules.pension_rule;
+        let owed: i64 = self
+            .people
+            .values()
+            .map(|per| per.get_pension_payment(rule) as i64)
+            .sum();
+
+        let share = if owed > self.pensions.budget {
+            self.pensions.budget.max(0) as f32 / owed as f32
+        } else {
+            1.
+        };
+
+        let mut paid: i64 = 0;
+        for per in self.people.values_mut() {
+            let payment = (per.get_pension_payment(rule) as f32 * share) as i64;
+            per.balance += payment as f32;
+            paid += payment;
+        }
+
+        self.pensions.paid_last_month = paid;
+        self.pensions.retiree_count = self
+            .people
+            .values()
+            .filter(|per| per.job == Job::Retired)
+            .count() as i32;
+
+        paid
+    }
+
+    /// What the healthcare and education budgets and the vaccines given cost this month,
+    /// less what patients paid towards their hospital stays
+    pub fn get_budget_spending(&self) -> i64 {
+        self.healthcare.budget
+            + self.education.budget
+            + self.education.tuition_owed
+            + self.epidemic.vaccinations_last_month as i64 * VACCINE_COST
+            - self.healthcare.charges_paid_last_month
+    }
+
+    /// Compares the education budget with what schools need for everyone in education,
+    /// and counts who is studying
+    pub fn update_education_funding(&mut self) {
+        let (pupils, students) = self
+            .people
+            .values()
+            .fold((0, 0), |(pupils, students), per| {
+                let student = per.studying_for.is_some();
+                let pupil = (SCHOOL_STARTING_AGE..18).contains(&per.age) || student;
+
+                (pupils + pupil as i64, students + student as i32)
+            });
+
+        self.education.students = students;
+        self.education.funding = if pupils == 0 {
+            1.
+        } else {
+            let budget_per_pupil = self.education.budget as f32 / pupils as f32;
+            (budget_per_pupil / EXPECTED_SPENDING_PER_PUPIL as f32)
+                .clamp(MINIMUM_EDUCATION_FUNDING, MAXIMUM_EDUCATION_FUNDING)
+        };
+    }
+
     pub fn check_healthcare_capacity(
         &self,
         new_total_capacity: i32,
@@ -133,9 +271,95 @@ impl GameState {
         }
     }
 
+    /// Collects rent and mortgage payments and moves people into vacant homes. Rent and what new residents
+    /// pay up front go to the housing businesses. Returns what the government spent on housing subsidies
+    pub fn pay_housing(&mut self) -> i64 {
+        let mut subsidised_count = 0;
+        let mut subsidy_cost: i64 = 0;
+        let mut rent_collected = 0.;
+        let mut sales_tax = 0.;
+
+        self.housing.evictions_last_month = 0;
+        self.housing.adjust_market(&self.rules.rent_cap_rule);
+
+        for per in self.people.values_mut() {
+            let subsidy =
+                per.get_housing_subsidy(&self.rules.housing_subsidy_rule, subsidised_count);
+            if subsidy > 0 {
+                per.balance += subsidy as f32;
+                subsidy_cost += subsidy as i64;
+                subsidised_count += 1;
+            }
+
+            rent_collected += per.pay_housing(
+                &mut self.housing,
+                &self.rules.rent_cap_rule,
+                &self.rules.sales_tax_rule,
+                &mut sales_tax,
+            );
+        }
+
+        // Someone whose partner has a home already lives there
+        let housed_partners: HashSet<Uuid> = self
+            .people
+            .values()
+            .filter(|per| per.has_home())
+            .filter_map(|per| per.partner_id)
+            .collect();
+
+        // Move people in after everyone has paid, so homes freed by evictions can be let straight away
+        let mut moved_in: Vec<Uuid> = Vec::new();
+        for per in self.people.values_mut() {
+            if per.has_home() || housed_partners.contains(&per.id) {
+                continue;
+            }
+
+            rent_collected += per.find_housing(
+                &mut self.housing,
+                &self.rules.rent_cap_rule,
+                &self.rules.sales_tax_rule,
+                &mut sales_tax,
+                &mut self.rng,
+            );
+            if per.has_home() {
+                moved_in.push(per.id);
+            }
+        }
+
+        for id in moved_in {
+            self.move_out(id);
+        }
+
+        self.government_balance += sales_tax as i64;
+        self.finance_data.sales_tax_last_month += sales_tax as i64;
+
+        self.housing.homeless_count =
+            self.people.values().filter(|p| p.is_homeless()).count() as i32;
+
+        let landlords: Vec<&mut Business> = self
+            .businesses
+            .values_mut()
+            .filter(|b| b.product_type == ProductType::Housing)
+            .collect();
+
+        if !landlords.is_empty() {
+            let rent_share = rent_collected as f64 / landlords.len() as f64;
+            for landlord in landlords {
+                landlord.balance += rent_share;
+            }
+        }
+
+        subsidy_cost
+    }
+
     pub fn get_spare_budget(&self) -> i64 {
         let spare_budget = self.government_balance
-            - (self.healthcare.budget + self.welfare_budget + self.business_budget);
+            - (self.healthcare.budget
+                + self.welfare_budget
+                + self.business_budget
+                + self.education.budget
+                + self.pensions.budget
+                + self.epidemic.vaccination_budget);
         if spare_budget < 0 {
             return 0;
         }
@@ -146,12 +370,13 @@ impl GameState {
     pub fn day_pass(
         &mut self,
         day: i32,
-        app_handle: Option<&tauri::AppHandle>,
+        sink: &dyn GameEventSink,
         config: &Config,
     ) -> IncResult<()> {
         let date = self.date.clone();
         let mut food_coverage = 0;
         let mut unemployed_food_coverage = 0;
+        let mut sales_tax = 0.;
 
         let mut total_monthly_income: i64 = 0;
 
@@ -170,7 +395,10 @@ impl GameState {
         let mut death_ages_total = 0;
 
         let mut death_queue: Vec<Uuid> = Vec::new();
-        let mut new_birth_count: i32 = 0;
+        let mut mothers: Vec<Uuid> = Vec::new();
+
+        let household_balances = self.get_household_balances();
+        let mut food_bills: BTreeMap<Uuid, f32> = BTreeMap::new();
 
         for per in self.people.values_mut() {
             let key = match per.age {
@@ -187,19 +415,34 @@ impl GameState {
                 Error::Danger(format!("Could not find age range '{}' in age ranges.", key))
             })? = json!(int64 + 1); // Increment counter of age ranges for state
 
+            let household_balance = per
+                .household_id
+                .and_then(|id| household_balances.get(&id))
+                .copied();
+            let mut food_bill = 0.;
+
             let die = per.day_pass(
                 day,
                 &mut self.healthcare,
+                &mut self.education,
                 &date,
                 &mut self.businesses,
                 &mut self.purchases,
                 &mut self.total_possible_purchases,
+                &mut sales_tax,
+                household_balance,
+                &mut food_bill,
                 &self.rules,
                 &mut food_coverage,
                 &mut unemployed_food_coverage,
+                config,
+                &mut self.rng,
             )?;
 
             total_monthly_income += (per.salary / 12) as i64;
+            if food_bill != 0. {
+                food_bills.insert(per.id, food_bill);
+            }
 
             if die {
                 death_queue.push(per.id);
@@ -207,29 +450,38 @@ impl GameState {
             }
 
             total_welfare += per.welfare;
-            if per.job == Job::Unemployed && !per.homeless && per.age >= 18 {
+            if per.job == Job::Unemployed && !per.is_homeless() && per.age >= 18 {
                 total_welfare_unemployed += per.welfare;
                 self.unemployed_count += 1;
             }
 
-            if per.due_birth(&date, &mut self.healthcare, &self.rules) {
-                new_birth_count += 1;
+            if per.due_birth(&date, &mut self.healthcare, &self.rules, &mut self.rng) {
+                mothers.push(per.id);
             }
         }
 
+        self.share_food_bills(&food_bills);
+
         self.welfare_owed += ((food_coverage + unemployed_food_coverage) * 4) as i64;
+        self.government_balance += sales_tax as i64;
+        self.finance_data.sales_tax_last_month += sales_tax as i64;
         self.finance_data.average_monthly_income =
             (total_monthly_income / self.people.len() as i64) as i32;
 
         for id in death_queue.iter() {
+            self.settle_estate(*id, &death_queue)?;
+            self.remove_from_family(*id);
+
             let per = self.people.get_mut(id).ok_or(Error::DangerUnexpected)?;
             death_ages_total += per.age;
 
             let healthcare_group = get_healthcare_group(per.age, &mut self.healthcare);
-            if let Some(_) = per.days_left_in_hospital {
+            if per.days_left_in_hospital.is_some() {
                 healthcare_group.current_capacity += 1;
             }
 
+            per.leave_home(&mut self.housing);
+
             let per_cpy = per.clone();
             self.resign_if_employed(per_cpy);
             self.people.remove(id);
@@ -241,19 +493,28 @@ impl GameState {
 
         self.deaths_in_last_month.push(death_queue.len());
 
-        for _ in 0..new_birth_count {
-            let infant =
-                Person::new_infant(config, self.tax_rate, &self.rules.tax_rule, date.clone())?;
-            self.people.insert(infant.id, infant);
+        let new_birth_count = mothers.len() as i32;
+        for mother_id in mothers {
+            let infant = Person::new_infant(
+                config,
+                self.tax_rate,
+                &self.rules.tax_rule,
+                &self.income_tax,
+                date.clone(),
+                &mut self.rng,
+            )?;
+            self.add_newborn(mother_id, infant);
         }
 
         self.births_in_last_month.push(new_birth_count);
 
+        self.spread_disease(day, sink)?;
+
         self.healthcare.births_per_month = 0;
         self.healthcare.deaths_per_month = 0;
 
         for day_amount in &self.births_in_last_month.array {
-            self.healthcare.births_per_month += *day_amount as i32;
+            self.healthcare.births_per_month += *day_amount;
         }
 
         for day_amount in &self.deaths_in_last_month.array {
@@ -266,34 +527,44 @@ impl GameState {
             (total_welfare_unemployed as f32 / self.unemployed_count as f32) as i32;
 
         self.expected_balance = (self.government_balance
-            - (self.business_budget + self.welfare_budget + self.healthcare.budget))
+            - (self.business_budget
+                + self.welfare_budget
+                + self.healthcare.budget
+                + self.education.budget
+                + self.pensions.budget
+                + self.epidemic.vaccination_budget))
             + (self.finance_data.expected_business_income
-                + self.finance_data.expected_person_income);
+                + self.finance_data.expected_person_income)
+            - self.public_debt.get_monthly_interest();
 
         // Update graph data
         self.population_graph_data.push(self.people.len() as i64);
         self.births_graph_data.push(new_birth_count as i64);
         self.deaths_graph_data.push(death_queue.len() as i64);
-        self.life_expectancy_graph_data.push(self.healthcare.life_expectancy as i64);
-        self.hospital_usage_capacity_graph_data.push(self.healthcare.get_current_capacity() as i64);
-        self.average_welfare_graph_data.push(self.average_welfare as i64);
-        self.average_unemployed_welfare_graph_data.push(self.average_welfare_unemployed as i64);
-        self.government_balance_graph_data.push(self.government_balance);
-        self.government_balance_prediction_graph_data.push(self.expected_balance);
+        self.life_expectancy_graph_data
+            .push(self.healthcare.life_expectancy as i64);
+        self.hospital_usage_capacity_graph_data
+            .push(self.healthcare.get_current_capacity() as i64);
+        self.infected_graph_data
+            .push(self.epidemic.infected_count as i64);
+        self.average_welfare_graph_data
+            .push(self.average_welfare as i64);
+        self.average_unemployed_welfare_graph_data
+            .push(self.average_welfare_unemployed as i64);
+        self.government_balance_graph_data
+            .push(self.government_balance);
+        self.government_balance_prediction_graph_data
+            .push(self.expected_balance);
+        self.inflation_graph_data
+            .push((self.prices.inflation * 10000.) as i64); // In hundredths of a percent
 
         self.spare_budget = self.get_spare_budget();
-        self.emit_daily_events(app_handle);
+        self.emit_daily_events(sink)?;
 
         Ok(())
     }
 
-    pub fn emit_daily_events(&self, app_handle: Option<&AppHandle>) {
-        if app_handle.is_none() {
-            return;
-        }
-
-        let app_handle = app_handle.unwrap();
-
+    pub fn emit_daily_events(&self, sink: &dyn GameEventSink) -> IncResult<()> {
         update_app(
             App::Finance,
             json!({
@@ -303,13 +574,14 @@ impl GameState {
                 "average_welfare": self.average_welfare,
                 "average_welfare_unemployed": self.average_welfare_unemployed,
                 "used_business_budget": (self.rules.business_funding_rule.fund * self.rules.business_funding_rule.business_count as i64),
-                "used_welfare_budget": ((self.rules.cover_food_rule.people_count * 4) + (self.rules.cover_food_unemployed_rule.people_count * 4)) as i64,
+                "used_welfare_budget": ((self.rules.cover_food_rule.people_count * 4) + (self.rules.cover_food_unemployed_rule.people_count * 4)) as i64 + self.rules.housing_subsidy_rule.budget_cost,
                 "spare_hospital_capacity": (self.healthcare.total_capacity - (self.healthcare.childcare.total_capacity + self.healthcare.adultcare.total_capacity + self.healthcare.eldercare.total_capacity)),
                 "expected_balance": self.expected_balance,
+                "inflation": self.prices.inflation,
             }),
-            app_handle,
+            sink,
             AppUpdateType::Day,
-        );
+        )?;
 
         update_app(
             App::Healthcare,
@@ -328,10 +600,14 @@ impl GameState {
                 "deaths_graph_data": get_daily_data(&self.deaths_graph_data),
                 "life_expectancy_graph_data": get_daily_data(&self.life_expectancy_graph_data),
                 "hospital_usage_capacity_graph_data": get_daily_data(&self.hospital_usage_capacity_graph_data),
+                "infected_count": self.epidemic.infected_count,
+                "immune_count": self.epidemic.immune_count,
+                "quarantined": self.epidemic.quarantined,
+                "infected_graph_data": get_daily_data(&self.infected_graph_data),
             }),
-            app_handle,
+            sink,
             AppUpdateType::Day,
-        );
+        )?;
 
         update_app(
             App::Welfare,
@@ -343,9 +619,9 @@ impl GameState {
                 "average_welfare_graph_data": get_daily_data(&self.average_welfare_graph_data),
                 "average_unemployed_welfare_graph_data": get_daily_data(&self.average_unemployed_welfare_graph_data),
             }),
-            app_handle,
+            sink,
             AppUpdateType::Day,
-        );
+        )?;
 
         update_app(
             App::Business,
@@ -353,9 +629,9 @@ impl GameState {
                 "business_count": self.businesses.len() as i32,
                 "business_budget": self.business_budget,
             }),
-            app_handle,
+            sink,
             AppUpdateType::Day,
-        );
+        )?;
 
         // app_handle.emit_all("debug_payload",  json! ({
         //     "Population": self.people.len(),
@@ -365,34 +641,57 @@ impl GameState {
         //     "Monthly Deaths": self.healthcare.deaths_per_month,
         //     "Unemployed Count": self.unemployed_count,
         // })).unwrap();
+
+        Ok(())
     }
 
-    pub fn month_pass(&mut self, app_handle: &AppHandle, config: &Config) -> IncResult<()> {
+    pub fn month_pass(&mut self, sink: &dyn GameEventSink, config: &Config) -> IncResult<()> {
         self.finance_data.expected_person_income = 0;
+        self.income_tax.reset_revenue();
+
+        let retirement_age = Person::get_retirement_age(config, &self.rules.pension_rule);
+        let opening_balances: BTreeMap<Uuid, f32> = self
+            .people
+            .values()
+            .map(|per| (per.id, per.balance))
+            .collect();
 
         for person in self.people.values_mut() {
-            person.business_this_month = None;
+            person.businesses_this_month.clear();
 
-            let tax_rate = Person::get_tax_rate(&self.rules.tax_rule, self.tax_rate, person.salary);
-            person.calculate_demand(person.salary, None, tax_rate)?;
+            let basic_rate =
+                Person::get_basic_tax_rate(&self.rules.tax_rule, self.tax_rate, person.salary);
+            let tax_by_band = self.income_tax.get_tax_by_band(basic_rate, person.salary);
+            let tax_rate = self.income_tax.get_tax_rate(basic_rate, person.salary);
+            person.calculate_demand(config, person.salary, None, tax_rate, &mut self.rng)?;
 
             match person.job {
                 Job::BusinessOwner(bid) | Job::Employee(bid) => {
                     let business = self.businesses.get_mut(&bid);
                     if let Some(business) = business {
-                        let tax_payment = (person.salary as f32 / 12.) * tax_rate;
+                        if let Job::BusinessOwner(_) = person.job {
+                            let draw = business.pay_owner(person);
+                            person.set_salary((draw * 12.) as i32);
+                        } else {
+                            person.business_pay(business, business.employee_salary as f64 / 12.);
+                        }
+
+                        let tax_payment = tax_by_band.iter().sum::<f32>() / 12.;
                         self.finance_data.expected_person_income += tax_payment as i64;
 
-                        person.pay_tax(
-                            &mut self.government_balance,
-                            (person.salary as f32 / 12.) * tax_rate,
-                        );
-                        person.business_pay(business, business.employee_salary as f64 / 12.);
+                        for (revenue, tax) in
+                            self.income_tax.revenue_by_band.iter_mut().zip(&tax_by_band)
+                        {
+                            *revenue += (tax / 12.) as i64;
+                        }
+
+                        person.pay_tax(&mut self.government_balance, tax_payment);
+
+                        person.record_career_salary();
 
-                        if person.age >= 65 && chance_one_in(60) {
-                            // Retired
-                            person.set_salary(15800); // TODO: vary pension salary
-                            person.job = Job::Retired;
+                        // Owners keep their business until they die and it is passed on
+                        if person.job == Job::Employee(bid) && person.age >= retirement_age {
+                            person.retire(config);
 
                             let emp_idx = business.employees.iter().position(|&id| id == person.id);
                             if let Some(idx) = emp_idx {
@@ -401,48 +700,33 @@ impl GameState {
                         }
                     } else {
                         person.job = Job::Unemployed;
-                        person.set_salary(generate_unemployed_salary());
+                        person.set_salary(generate_unemployed_salary(&mut self.rng));
                     }
-
-                    // business.pay_owner(person);
                 }
 
+                Job::Unemployed if person.age >= retirement_age => person.retire(config),
+
                 _ => (),
             };
+        }
 
-            for i in 0..person.debts.len() {
-                // TODO: Add functionality based on spending behaviour
-                if !Debt::required_to_pay(person) {
-                    break;
-                }
-
-                let debt = &mut person.debts[i];
-
-                if debt.debt_type == DebtType::Education
-                    && person.age < (18 + person.years_in_higher_education)
-                {
-                    continue;
-                }
-
-                if debt.owed < debt.minimum_monthly_payoff {
-                    person.balance -= debt.owed;
-                    person.debts.remove(i);
-                    person.get_monthly_debt_cost();
-
-                    continue;
-                }
-
-                debt.owed -= debt.minimum_monthly_payoff;
+        self.pay_household_debts();
+        let pension_cost = self.pay_pensions();
+        let childcare_cost = self.settle_households(&opening_balances);
+        self.form_partnerships()?;
+        let housing_subsidy_cost = self.pay_housing();
+        self.update_education_funding();
 
-                // Add functionality to welfare if they can't afford debts
-                person.balance -= debt.minimum_monthly_payoff;
+        let mut demand: BTreeMap<ProductType, f32> = BTreeMap::new();
+        for person in self.people.values() {
+            for (product_type, product_demand) in &person.demand {
+                *demand.entry(*product_type).or_insert(0.) += product_demand;
             }
         }
 
-        let mut reinvestment_budgets: Vec<(Uuid, f64)> = Vec::new();
-        let mut total_reinvestment_budget = 0.;
-
-        let mut bus_removal_queue: Vec<Uuid> = Vec::new();
+        // Each product has its own market, so budgets are only compared with businesses selling the same product
+        let mut reinvestment_budgets: BTreeMap<ProductType, Vec<(Uuid, f64)>> = BTreeMap::new();
+        let mut total_reinvestment_budgets: BTreeMap<ProductType, f64> = BTreeMap::new();
 
         self.finance_data.expected_business_income = 0;
 
@@ -456,30 +740,21 @@ impl GameState {
         let required_new_businesses = (self.unemployed_count as f32 / portion) as usize;
 
         if required_new_businesses != 0 {
-            let owners: Vec<_> = self
+            let founder_ids: Vec<Uuid> = self
                 .people
-                .values_mut()
-                .filter(|p| p.balance >= 15000. && p.age >= 18)
+                .values()
+                .filter(|p| {
+                    p.balance >= 15000.
+                        && p.age >= 18
+                        && !matches!(p.job, Job::BusinessOwner(_) | Job::Retired)
+                })
                 .take(required_new_businesses)
+                .map(|p| p.id)
                 .collect();
-            for bus_owner in owners {
-                let mut business = Business::default();
-                let start_balance = bus_owner.balance * 0.45; // TODO: improve me
-
-                business.generate_midgame(ProductType::Leisure, config, start_balance as f64); // TODO: support multiple product types
 
-                business.owner_id = bus_owner.id;
-
-                // TODO: resign from old job
-
-                // let owner_cpy = bus_owner.clone();
-
-                // self.resign_if_employed(owner_cpy);
-
-                // bus_owner.set_salary(0);
-                // bus_owner.job = Job::BusinessOwner(business.id);
-
-                self.businesses.insert(business.id, business);
+            for founder_id in founder_ids {
+                let product_type = ProductType::most_underserved(&self.businesses, &demand);
+                self.found_business(founder_id, product_type, config)?;
             }
         }
 
@@ -491,11 +766,6 @@ impl GameState {
             );
             business.last_month_income = business.balance - business.last_month_balance;
 
-            // TODO: make me more varied
-            if business.balance <= 0. {
-                bus_removal_queue.push(business.id);
-            }
-
             let tax_rate = Business::get_tax_rate(
                 &self.rules.business_tax_rule,
                 business.last_month_income,
@@ -515,8 +785,13 @@ impl GameState {
                 business.balance * as_decimal_percent!(business.marketing_cost_percentage) as f64;
 
             if reinvesment_budget > 0. {
-                total_reinvestment_budget += reinvesment_budget;
-                reinvestment_budgets.push((business.id, reinvesment_budget));
+                *total_reinvestment_budgets
+                    .entry(business.product_type)
+                    .or_insert(0.) += reinvesment_budget;
+                reinvestment_budgets
+                    .entry(business.product_type)
+                    .or_default()
+                    .push((business.id, reinvesment_budget));
             }
 
             total_business_income += business.last_month_income as u128;
@@ -528,79 +803,77 @@ impl GameState {
         self.business_data.average_employees =
             (total_employees / self.businesses.len() as u64) as i32;
 
-        let mut remaining_market_percentage: f32 = 100.;
-        let mut cost_per_percent = 0.;
+        self.update_prices();
+        self.check_bankruptcies(funded_businesses, sink)?;
 
         let purchase_rate = self.purchases as f32 / self.total_possible_purchases as f32;
 
-        let mut demand: f32 = 0.;
-        for person in self.people.values() {
-            // TODO: do this for all product types
-            demand += person.demand[&ProductType::Leisure];
-        }
-
-        let people_cpy = &mut self.people.clone();
-        let unemployed_people: &mut Vec<&mut Person> = &mut people_cpy
-            .values_mut()
-            .filter(|p| p.job == Job::Unemployed && p.age >= 18)
-            .collect();
+        for (product_type, budgets) in &reinvestment_budgets {
+            let total_reinvestment_budget = total_reinvestment_budgets[product_type];
+            let product_demand = demand.get(product_type).copied().unwrap_or(0.);
 
-        for (i, (bid, budget)) in reinvestment_budgets.iter().enumerate() {
-            let maximum_percentage = (budget / total_reinvestment_budget) * 100.;
+            let mut remaining_market_percentage: f32 = 100.;
+            let mut cost_per_percent = 0.;
 
-            if i == 0 {
-                cost_per_percent = (budget / maximum_percentage) as f32;
-            }
+            for (i, (bid, budget)) in budgets.iter().enumerate() {
+                let maximum_percentage = (budget / total_reinvestment_budget) * 100.;
 
-            let mut assigned_percent = (budget / cost_per_percent as f64) as f32;
-
-            if (remaining_market_percentage - assigned_percent) < 0. {
-                assigned_percent = remaining_market_percentage;
-            }
+                if i == 0 {
+                    cost_per_percent = (budget / maximum_percentage) as f32;
+                }
 
-            let business = self.businesses.get_mut(bid).ok_or_else(|| {
-                Error::Danger("Could not get business from reinvestment budgets list.".to_string())
-            })?;
+                let mut assigned_percent = (budget / cost_per_percent as f64) as f32;
 
-            business.get_new_market(
-                assigned_percent,
-                cost_per_percent,
-                &mut self.people,
-                unemployed_people,
-                demand,
-                purchase_rate,
-            )?;
-            business.last_month_balance = business.balance;
+                if (remaining_market_percentage - assigned_percent) < 0. {
+                    assigned_percent = remaining_market_percentage;
+                }
 
-            remaining_market_percentage -= assigned_percent;
+                let business = self.businesses.get_mut(bid).ok_or_else(|| {
+                    Error::Danger(
+                        "Could not get business from reinvestment budgets list.".to_string(),
+                    )
+                })?;
+
+                business.get_new_market(
+                    assigned_percent,
+                    cost_per_percent,
+                    &mut self.people,
+                    product_demand,
+                    purchase_rate,
+                    Business::get_sales_tax_rate(&self.rules.sales_tax_rule, *product_type),
+                    &mut self.rng,
+                )?;
+                business.last_month_balance = business.balance;
+
+                remaining_market_percentage -= assigned_percent;
+            }
         }
 
         self.rules.business_funding_rule.budget_cost =
             self.rules.business_funding_rule.fund * (self.businesses.len() as i64);
 
-        let mut unemployed_count = 0;
-
-        // Sync people to unemployed people
-        for per_cpy in unemployed_people {
-            if per_cpy.job != Job::Unemployed {
-                let per = self.people.get_mut(&per_cpy.id).unwrap();
-                per.job = per_cpy.job.clone();
-                per.set_salary(per_cpy.salary);
-            } else {
-                unemployed_count += 1;
-            }
-        }
+        self.run_labour_market(config)?;
 
-        for id in bus_removal_queue {
-            self.businesses.remove(&id);
-        }
+        let unemployed_count = self
+            .people
+            .values()
+            .filter(|p| p.job == Job::Unemployed && p.age >= 18)
+            .count() as i32;
 
         let percentage = (unemployed_count as f32 / self.people.len() as f32) * 100.;
 
-        if percentage >= 4. {
-            app_handle.emit_all("unemployed_high", json!({ "unemployed_count": unemployed_count, "percent": percentage, "severity": "mild" })).unwrap();
-        } else if percentage >= 1. {
-            app_handle.emit_all("unemployed_high", json!({ "unemployed_count": unemployed_count, "percent": percentage, "severity": "high" })).unwrap();
+        let severity = match percentage {
+            p if p >= 4. => Some(UnemploymentSeverity::Mild),
+            p if p >= 1. => Some(UnemploymentSeverity::High),
+            _ => None,
+        };
+
+        if let Some(severity) = severity {
+            sink.emit(GameEvent::UnemployedHigh {
+                unemployed_count,
+                percent: percentage,
+                severity,
+            })?;
         }
 
         // self.government_balance -= self.welfare_owed
@@ -609,24 +882,40 @@ impl GameState {
 
         let losses = self.welfare_owed
             + (*funded_businesses as i64 * self.rules.business_funding_rule.fund)
-            + self.healthcare.budget;
+            + self.get_budget_spending()
+            + pension_cost
+            + housing_subsidy_cost
+            + childcare_cost;
+        self.education.tuition_owed = 0;
 
         self.government_balance -= losses;
+        let debt_interest = self.service_public_debt();
 
         // Update graph data
-        self.average_monthly_income_graph_data.push(self.finance_data.average_monthly_income as i64);
-        self.government_losses_graph_data.push(losses);
-        self.business_count_graph_data.push(self.businesses.len() as i64);
-        self.average_employees_graph_data.push(self.business_data.average_employees as i64);
-        self.business_average_monthly_income_graph_data.push(self.business_data.average_monthly_income);
-        self.unemployed_count_graph_data.push(self.unemployed_count as i64);
-            
+        self.average_monthly_income_graph_data
+            .push(self.finance_data.average_monthly_income as i64);
+        self.government_losses_graph_data
+            .push(losses + debt_interest);
+        self.business_count_graph_data
+            .push(self.businesses.len() as i64);
+        self.average_employees_graph_data
+            .push(self.business_data.average_employees as i64);
+        self.business_average_monthly_income_graph_data
+            .push(self.business_data.average_monthly_income);
+        self.unemployed_count_graph_data
+            .push(self.unemployed_count as i64);
+        self.household_debt_graph_data
+            .push(self.household_debt.total_owed);
+        self.public_debt_graph_data.push(self.public_debt.owed);
+
         update_app(
             App::Finance,
             json!({
                 "average_monthly_income": self.finance_data.average_monthly_income,
                 "expected_person_income": self.finance_data.expected_person_income,
                 "expected_business_income": self.finance_data.expected_business_income,
+                "sales_tax_last_month": self.finance_data.sales_tax_last_month,
+                "income_tax": self.income_tax,
                 "welfare_budget": self.welfare_budget,
                 "business_budget": self.business_budget,
                 "healthcare_budget": self.healthcare.budget,
@@ -635,28 +924,52 @@ impl GameState {
                 "government_balance_prediction_graph_data": get_monthly_data(&self.government_balance_prediction_graph_data, false),
                 "average_monthly_income_graph_data": get_monthly_data(&self.average_monthly_income_graph_data, false),
                 "government_losses_graph_data": get_monthly_data(&self.government_losses_graph_data, false),
+                "household_debt": self.household_debt,
+                "household_debt_graph_data": get_monthly_data(&self.household_debt_graph_data, false),
+                "public_debt": self.public_debt,
+                "public_debt_graph_data": get_monthly_data(&self.public_debt_graph_data, false),
+                "prices": self.prices,
+                "inflation_graph_data": get_monthly_data(&self.inflation_graph_data, false),
             }),
-            app_handle,
+            sink,
             AppUpdateType::Month,
-        );
+        )?;
 
         update_app(
             App::Healthcare,
             json!({
                 "life_expectancy": self.healthcare.life_expectancy,
+                "charges_paid_last_month": self.healthcare.charges_paid_last_month,
+                "charges_unpaid_last_month": self.healthcare.charges_unpaid_last_month,
+                "infections_last_month": self.epidemic.infections_last_month,
+                "vaccinations_last_month": self.epidemic.vaccinations_last_month,
             }),
-            app_handle,
+            sink,
             AppUpdateType::Month,
-        );
+        )?;
 
         update_app(
             App::Welfare,
             json!({
                 "unemployed_count_graph_data": get_monthly_data(&self.unemployed_count_graph_data, false),
+                "homeless_count": self.housing.homeless_count,
+                "evictions_last_month": self.housing.evictions_last_month,
+                "market_rent": self.housing.market_rent,
+                "vacant_homes": self.housing.vacant_homes(),
+                "vacancies": self.labour_market.vacancies,
+                "hires_last_month": self.labour_market.hires_last_month,
+                "quits_last_month": self.labour_market.quits_last_month,
+                "job_switches_last_month": self.labour_market.job_switches_last_month,
+                "minimum_wage_layoffs_last_month":
+                    self.labour_market.minimum_wage_layoffs_last_month,
+                "students": self.education.students,
+                "graduates_last_month": self.education.graduates_last_month,
+                "pensions_paid_last_month": self.pensions.paid_last_month,
+                "retiree_count": self.pensions.retiree_count,
             }),
-            app_handle,
+            sink,
             AppUpdateType::Month,
-        );
+        )?;
 
         update_app(
             App::Business,
@@ -667,14 +980,191 @@ impl GameState {
                 "average_employees_graph_data": get_monthly_data(&self.average_employees_graph_data, false),
                 "average_monthly_income_graph_data": get_monthly_data(&self.average_monthly_income_graph_data, false),
             }),
-            app_handle,
+            sink,
             AppUpdateType::Month,
-        );
+        )?;
 
         self.healthcare.month_unhospitalised_count = 0;
+        self.healthcare.charges_paid_last_month = 0;
+        self.healthcare.charges_unpaid_last_month = 0;
+        self.finance_data.sales_tax_last_month = 0;
+        self.public_debt.borrowed_last_month = 0;
+        self.public_debt.repaid_last_month = 0;
+        self.epidemic.infections_last_month = 0;
+        self.epidemic.vaccinations_last_month = 0;
+        self.education.graduates_last_month = 0;
         self.total_possible_purchases = 0;
         self.purchases = 0;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::config::load_config,
+        entities::person::{housing::Housing, person::EducationLevel},
+    };
+
+    #[test]
+    fn founder_leaves_their_job_to_run_the_new_business() {
+        let config = load_config().unwrap();
+        let mut state = GameState::default();
+
+        let (employer_id, founder_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let employer = Business {
+            id: employer_id,
+            employees: vec![founder_id],
+            ..Business::default()
+        };
+        let founder = Person {
+            id: founder_id,
+            age: 40,
+            balance: 20000.,
+            job: Job::Employee(employer_id),
+            ..Person::default()
+        };
+        state.businesses.insert(employer_id, employer);
+        state.people.insert(founder_id, founder);
+
+        let bid = state
+            .found_business(founder_id, ProductType::Leisure, &config)
+            .unwrap();
+
+        let start_capital = 20000. * FOUNDER_START_CAPITAL;
+        let founder = &state.people[&founder_id];
+        let business = &state.businesses[&bid];
+        assert!(state.businesses[&employer_id].employees.is_empty());
+        assert!(founder.job == Job::BusinessOwner(bid));
+        assert_eq!(founder.balance, 20000. - start_capital);
+        assert_eq!(founder.salary, (business.get_owner_draw() * 12.) as i32);
+        assert_eq!(business.owner_id, founder_id);
+        assert_eq!(business.balance, start_capital as f64);
+    }
+
+    #[test]
+    fn landlords_are_paid_what_new_renters_pay_up_front() {
+        let mut state = GameState::default();
+        state.housing.total_homes = 10;
+        state.housing.market_rent = 500;
+
+        let landlord = Business {
+            id: Uuid::new_v4(),
+            product_type: ProductType::Housing,
+            ..Business::default()
+        };
+        let renter = Person {
+            id: Uuid::new_v4(),
+            age: 30,
+            salary: 60000,
+            balance: 1000.,
+            credit_score: 70,
+            housing: Housing::Homeless,
+            ..Person::default()
+        };
+        let (landlord_id, renter_id) = (landlord.id, renter.id);
+        state.businesses.insert(landlord_id, landlord);
+        state.people.insert(renter_id, renter);
+
+        state.pay_housing();
+
+        let renter = &state.people[&renter_id];
+        assert!(renter.has_home());
+        assert_eq!(
+            state.businesses[&landlord_id].balance,
+            (1000. - renter.balance) as f64
+        );
+    }
+
+    #[test]
+    fn pensions_are_cut_evenly_when_they_cost_more_than_the_budget() {
+        let mut state = GameState::default();
+        for salary in [12000, 36000] {
+            let per = Person {
+                id: Uuid::new_v4(),
+                job: Job::Retired,
+                salary,
+                ..Person::default()
+            };
+            state.people.insert(per.id, per);
+        }
+        let worker = Person {
+            id: Uuid::new_v4(),
+            salary: 60000,
+            ..Person::default()
+        };
+        state.people.insert(worker.id, worker);
+
+        state.pensions.budget = 5000;
+        assert_eq!(state.pay_pensions(), 4000);
+        assert_eq!(state.pensions.retiree_count, 2);
+
+        state.pensions.budget = 2000;
+        assert_eq!(state.pay_pensions(), 2000);
+
+        let mut balances: Vec<f32> = state.people.values().map(|per| per.balance).collect();
+        balances.sort_by(f32::total_cmp);
+        assert_eq!(balances, vec![0., 1000. + 500., 3000. + 1500.]);
+        assert_eq!(state.pensions.paid_last_month, 2000);
+    }
+
+    #[test]
+    fn hospital_charges_paid_by_patients_reduce_spending() {
+        let mut state = GameState::default();
+        state.healthcare.budget = 5000;
+        state.education.budget = 3000;
+        state.education.tuition_owed = 1000;
+        assert_eq!(state.get_budget_spending(), 9000);
+
+        state.healthcare.charges_paid_last_month = 400;
+        assert_eq!(state.get_budget_spending(), 8600);
+    }
+
+    #[test]
+    fn only_the_vaccines_given_are_paid_for() {
+        let mut state = GameState::default();
+        state.epidemic.vaccination_budget = VACCINE_COST * 300;
+        assert_eq!(state.get_budget_spending(), 0);
+
+        state.epidemic.vaccinations_last_month = 20;
+        assert_eq!(state.get_budget_spending(), VACCINE_COST * 20);
+    }
+
+    #[test]
+    fn education_funding_compares_the_budget_with_everyone_in_education() {
+        let mut state = GameState::default();
+        assert_eq!(state.education.funding, 1.);
+
+        let people = [
+            (10, None),
+            (17, None),
+            (20, Some(EducationLevel::Bachelors)),
+            (4, None),
+            (30, None),
+        ];
+        for (age, studying_for) in people {
+            let per = Person {
+                id: Uuid::new_v4(),
+                age,
+                studying_for,
+                ..Person::default()
+            };
+            state.people.insert(per.id, per);
+        }
+
+        state.education.budget = 2400;
+        state.update_education_funding();
+        assert_eq!(state.education.students, 1);
+        assert_eq!(state.education.funding, 0.8);
+
+        state.education.budget = 0;
+        state.update_education_funding();
+        assert_eq!(state.education.funding, MINIMUM_EDUCATION_FUNDING);
+
+        state.education.budget = 30000;
+        state.update_education_funding();
+        assert_eq!(state.education.funding, MAXIMUM_EDUCATION_FUNDING);
+    }
+}
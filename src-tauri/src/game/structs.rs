@@ -1,12 +1,15 @@
 use crate::{
-    common::util::{Date, SlotArray},
-    entities::{business::Business, person::person::Person},
+    common::util::{Date, GameRng, SlotArray},
+    entities::{
+        business::{Business, ProductType},
+        person::{debt::DebtType, person::Person},
+    },
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
-use super::events::App;
+use super::{events::App, public_debt::CreditRating};
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct TaxRule {
@@ -37,6 +40,18 @@ pub struct DenyAgeRule {
     pub maximum_age: i32,
 }
 
+#[derive(Default, Serialize, Deserialize)]
+pub struct HospitalChargesRule {
+    pub enabled: bool,
+    pub coverage: f32, // Share of each patient's hospital bill paid by the government
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct QuarantineRule {
+    pub enabled: bool,
+    pub minimum_infected: i32, // People are told to stay at home once this many are infected
+}
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct DenyHealthPercentageRule {
     pub enabled: bool,
@@ -58,7 +73,59 @@ pub struct CoverFoodUnemployedRule {
     pub budget_cost: i64,
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct RentCapRule {
+    pub enabled: bool,
+    pub maximum_rent: i32,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct HousingSubsidyRule {
+    pub enabled: bool,
+    pub people_count: i32,
+    pub subsidy: i32, // Paid towards each renter's monthly rent
+    pub maximum_salary: i32,
+    pub budget_cost: i64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct InheritanceTaxRule {
+    pub enabled: bool,
+    pub minimum_estate: i64, // Estates below this are not taxed
+    pub tax_rate: f32,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct TuitionRule {
+    pub enabled: bool,
+    pub coverage: f32, // Share of each new student's tuition paid by the government
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct PensionRule {
+    pub enabled: bool,
+    pub retirement_age: i32,
+    pub maximum_balance: i64, // Retirees with more savings than this are not paid, unless it is 0
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct MinimumWageRule {
+    pub enabled: bool,
+    pub minimum_wage: i32, // Lowest yearly salary businesses may pay their employees
+}
+
+/// Rates charged on top of the price of each product, between 0 and 1
+#[derive(Default, Serialize, Deserialize)]
+pub struct SalesTaxRule {
+    pub enabled: bool,
+    pub leisure: f32,
+    pub food: f32,
+    pub housing: f32,
+    pub furniture: f32,
+    pub healthcare_goods: f32,
+}
+
+#[derive(Default, Serialize, Deserialize)]
 pub struct GameStateRules {
     pub tax_rule: TaxRule,
     pub business_tax_rule: BusinessTaxRule,
@@ -67,40 +134,101 @@ pub struct GameStateRules {
     pub deny_health_percentage_rule: DenyHealthPercentageRule,
     pub cover_food_rule: CoverFoodRule,
     pub cover_food_unemployed_rule: CoverFoodUnemployedRule,
+    pub rent_cap_rule: RentCapRule,
+    pub housing_subsidy_rule: HousingSubsidyRule,
+    pub inheritance_tax_rule: InheritanceTaxRule,
+    pub tuition_rule: TuitionRule,
+    pub pension_rule: PensionRule,
+    pub hospital_charges_rule: HospitalChargesRule,
+    pub quarantine_rule: QuarantineRule,
+    pub sales_tax_rule: SalesTaxRule,
+    pub minimum_wage_rule: MinimumWageRule,
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct FinanceData {
     pub average_monthly_income: i32,
     pub expected_person_income: i64,
     pub expected_business_income: i64,
+    pub sales_tax_last_month: i64, // Collected from purchases as they are made
 }
 
-#[derive(Default)]
+/// A higher rate paid on the part of yearly income above the threshold
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TaxBracket {
+    pub threshold: i32,
+    pub rate: f32,
+}
+
+/// Income below the personal allowance is not taxed, income above it is taxed at the standard rate
+/// until it reaches the first bracket
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IncomeTaxState {
+    pub personal_allowance: i32,
+    pub brackets: Vec<TaxBracket>, // Ordered by threshold
+    pub revenue_by_band: Vec<i64>, // Collected this month, the standard rate band followed by each bracket
+}
+
+impl Default for IncomeTaxState {
+    fn default() -> Self {
+        Self {
+            personal_allowance: 10000,
+            brackets: vec![
+                TaxBracket {
+                    threshold: 50000,
+                    rate: 0.4,
+                },
+                TaxBracket {
+                    threshold: 150000,
+                    rate: 0.45,
+                },
+            ],
+            revenue_by_band: vec![0; 3],
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
 pub struct BusinessData {
     pub average_employees: i32,
     pub average_monthly_income: i64,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct GameState {
+    pub name: String, // Name of the save the game is stored in
     pub tax_rate: f32,
     pub business_tax_rate: f32,
-    pub businesses: HashMap<Uuid, Business>,
-    pub people: HashMap<Uuid, Person>,
+    pub businesses: BTreeMap<Uuid, Business>, // Ordered so that iterating is the same for the same seed
+    pub people: BTreeMap<Uuid, Person>,
+    pub households: BTreeMap<Uuid, Household>,
     pub date: Date,
 
+    pub seed: u64,
+    pub rng: GameRng,
+
     pub government_balance: i64, // This is expected to be quite large
 
     pub total_possible_purchases: u32,
     pub purchases: u32,
 
     pub rules: GameStateRules,
+    #[serde(skip)]
     pub open_apps: HashMap<App, bool>,
 
     pub births_in_last_month: SlotArray<i32>,
     pub deaths_in_last_month: SlotArray<usize>,
     pub healthcare: HealthcareState,
-
+    pub housing: HousingState,
+    pub labour_market: LabourMarketState,
+    pub education: EducationState,
+    pub pensions: PensionState,
+    pub household_debt: HouseholdDebtState,
+    pub epidemic: EpidemicState,
+
+    pub income_tax: IncomeTaxState,
+    pub public_debt: PublicDebtState,
+    pub prices: PriceState,
     pub finance_data: FinanceData,
 
     pub welfare_budget: i64,
@@ -121,7 +249,6 @@ pub struct GameState {
     pub expected_balance: i64,
 
     // Daily updates
-
     pub population_graph_data: SlotArray<i64>,
     pub births_graph_data: SlotArray<i64>,
     pub deaths_graph_data: SlotArray<i64>,
@@ -131,15 +258,18 @@ pub struct GameState {
     pub average_unemployed_welfare_graph_data: SlotArray<i64>,
     pub government_balance_graph_data: SlotArray<i64>,
     pub government_balance_prediction_graph_data: SlotArray<i64>,
+    pub infected_graph_data: SlotArray<i64>,
 
     // Monthly updates
-
     pub average_monthly_income_graph_data: SlotArray<i64>,
     pub government_losses_graph_data: SlotArray<i64>,
     pub business_count_graph_data: SlotArray<i64>,
     pub average_employees_graph_data: SlotArray<i64>,
     pub business_average_monthly_income_graph_data: SlotArray<i64>,
     pub unemployed_count_graph_data: SlotArray<i64>,
+    pub household_debt_graph_data: SlotArray<i64>,
+    pub public_debt_graph_data: SlotArray<i64>,
+    pub inflation_graph_data: SlotArray<i64>,
 }
 
 #[derive(Default, Clone, Copy, Serialize, Deserialize)]
@@ -149,7 +279,7 @@ pub struct HealthcareGroup {
     pub total_capacity: i32,
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct HealthcareState {
     pub cost_per_hospital_capacity: f32, // This is the cost per person capacity in a hospital for the government, each month
     pub month_unhospitalised_count: i32, // Number of patient that could not go to hospital because of the full capacity
@@ -166,6 +296,9 @@ pub struct HealthcareState {
 
     pub births_per_month: i32,
     pub deaths_per_month: i32,
+
+    pub charges_paid_last_month: i64, // Paid by patients for their hospital stays, goes towards running the hospitals
+    pub charges_unpaid_last_month: i64, // Billed to patients who could not pay, they owe it as medical debt
 }
 
 impl HealthcareState {
@@ -180,3 +313,143 @@ impl HealthcareState {
                 + (self.eldercare.total_capacity - self.eldercare.current_capacity))
     }
 }
+
+/// People who live together and share their money
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct Household {
+    pub id: Uuid,
+    pub member_ids: Vec<Uuid>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct HousingState {
+    pub total_homes: i32,
+    pub occupied_homes: i32,
+    pub market_rent: i32, // Average monthly rent of a newly let home
+    pub homeless_count: i32,
+    pub evictions_last_month: i32,
+}
+
+impl HousingState {
+    pub fn vacant_homes(&self) -> i32 {
+        self.total_homes - self.occupied_homes
+    }
+
+    /// Rents rise and new homes are built when there are few vacancies, a rent cap makes building less worthwhile
+    pub fn adjust_market(&mut self, rent_cap_rule: &RentCapRule) {
+        if self.total_homes == 0 {
+            return;
+        }
+
+        let vacancy_rate = self.vacant_homes() as f32 / self.total_homes as f32;
+
+        if vacancy_rate < 0.05 {
+            self.market_rent = (self.market_rent as f32 * 1.01) as i32;
+
+            let mut new_homes = (self.total_homes as f32 * 0.01).ceil() as i32;
+            if rent_cap_rule.enabled && rent_cap_rule.maximum_rent < self.market_rent {
+                new_homes /= 2;
+            }
+
+            self.total_homes += new_homes;
+        } else if vacancy_rate > 0.1 {
+            self.market_rent = (self.market_rent as f32 * 0.99) as i32;
+        }
+    }
+}
+
+/// Hiring and leaving jobs over the last month
+#[derive(Default, Serialize, Deserialize)]
+pub struct LabourMarketState {
+    pub vacancies: i32, // Jobs businesses could not fill
+    pub hires_last_month: i32,
+    pub quits_last_month: i32,
+    pub job_switches_last_month: i32, // Hires of people who left another job for a better paid one
+    pub minimum_wage_layoffs_last_month: i32, // Staff businesses could not afford to pay the minimum wage
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EducationState {
+    pub budget: i64,  // Monthly spending on schools and universities
+    pub funding: f32, // Budget per pupil compared to what schools expect, changes how many people keep studying
+    pub students: i32,
+    pub graduates_last_month: i32,
+    pub tuition_owed: i64, // Tuition covered by the government this month, paid at the end of it
+}
+
+impl Default for EducationState {
+    fn default() -> Self {
+        Self {
+            budget: 0,
+            funding: 1.,
+            students: 0,
+            graduates_last_month: 0,
+            tuition_owed: 0,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct PensionState {
+    pub budget: i64, // Pensions are cut evenly when they cost more than this
+    pub paid_last_month: i64,
+    pub retiree_count: i32,
+}
+
+/// What people owe across the country, summed up at the end of each month
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct HouseholdDebtState {
+    pub total_owed: i64,
+    pub owed_by_type: BTreeMap<DebtType, i64>,
+    pub indebted_households: i32,
+    pub average_household_debt: i64, // Among households that owe anything
+    pub people_in_arrears: i32,      // People who have missed a payment they still owe
+    pub defaults_last_month: i32,
+    pub new_credit_last_month: i64, // Borrowed to cover overdrawn balances
+    pub average_credit_score: i32,  // Among adults
+}
+
+/// Prices people pay compared to when they were first measured
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PriceState {
+    pub consumer_price_index: f32, // 100 when prices were first measured
+    pub inflation: f32,            // Yearly change in the index
+    pub wage_index: f32,           // How much wages have risen along with prices
+    pub average_prices: BTreeMap<ProductType, f32>, // Paid last month, including sales tax
+    pub recent_indexes: Vec<f32>,  // The index at the end of each of the last 13 months
+}
+
+impl Default for PriceState {
+    fn default() -> Self {
+        Self {
+            consumer_price_index: 100.,
+            inflation: 0.,
+            wage_index: 1.,
+            average_prices: BTreeMap::new(),
+            recent_indexes: Vec::new(),
+        }
+    }
+}
+
+/// Bonds the government has sold to cover its deficits
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct PublicDebtState {
+    pub owed: i64,
+    pub debt_to_income: f32, // Years of government income it would take to pay the debt back
+    pub credit_rating: CreditRating,
+    pub interest_rate: f32, // Yearly rate paid on everything owed, set by the credit rating
+    pub interest_paid_last_month: i64,
+    pub borrowed_last_month: i64,
+    pub repaid_last_month: i64,
+}
+
+/// Spread of infectious disease through the population
+#[derive(Default, Serialize, Deserialize)]
+pub struct EpidemicState {
+    pub vaccination_budget: i64, // Most spent on vaccines each month, only the doses given are paid for. The eldest are vaccinated first
+    pub infected_count: i32,
+    pub immune_count: i32,
+    pub quarantined: bool, // Whether the quarantine rule is in force
+    pub infections_last_month: i32,
+    pub vaccinations_last_month: i32,
+}
@@ -3,41 +3,50 @@
     windows_subsystem = "windows"
 )]
 
-mod common;
-mod entities;
-mod game;
-
-use common::filesystem::check_save_exists;
-use game::events::{
-    app_close, app_open, disable_rule, enable_rule, update_adultcare_capacity,
-    update_business_budget, update_business_tax_rate, update_childcare_capacity,
-    update_eldercare_capacity, update_healthcare_budget, update_rule, update_tax_rate,
-    update_welfare_budget,
+use incumbency_desktop::{
+    common::filesystem,
+    game::{control::GameControl, events, manager, structs::GameState},
 };
-use game::{manager::create_game, structs::GameState};
 use std::sync::{Arc, Mutex};
 
 #[tokio::main]
 async fn main() {
     tauri::Builder::default()
         .invoke_handler(tauri::generate_handler![
-            create_game,
-            check_save_exists,
-            app_close,
-            app_open,
-            enable_rule,
-            disable_rule,
-            update_rule,
-            update_tax_rate,
-            update_business_tax_rate,
-            update_healthcare_budget,
-            update_welfare_budget,
-            update_business_budget,
-            update_childcare_capacity,
-            update_adultcare_capacity,
-            update_eldercare_capacity
+            manager::create_game,
+            manager::load_game,
+            manager::reset_game,
+            manager::pause_game,
+            manager::resume_game,
+            manager::set_game_speed,
+            manager::step_game_day,
+            filesystem::check_save_exists,
+            filesystem::save_game,
+            filesystem::list_saves,
+            filesystem::delete_save,
+            events::app_close,
+            events::app_open,
+            events::list_rules,
+            events::enable_rule,
+            events::disable_rule,
+            events::update_rule,
+            events::update_tax_rate,
+            events::update_income_tax,
+            events::update_business_tax_rate,
+            events::update_healthcare_budget,
+            events::update_welfare_budget,
+            events::update_business_budget,
+            events::update_education_budget,
+            events::update_pension_budget,
+            events::update_vaccination_budget,
+            events::issue_bonds,
+            events::repay_public_debt,
+            events::update_childcare_capacity,
+            events::update_adultcare_capacity,
+            events::update_eldercare_capacity
         ])
         .manage(Arc::new(Mutex::new(GameState::default())))
+        .manage(GameControl::default())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
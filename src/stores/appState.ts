@@ -2,6 +2,6 @@ import { writable, type Writable } from "svelte/store";
 
 type AppState = "MainMenu" | "NewGameMenu" | "LoadGameMenu" | "MultiplayerMenu" | "SettingsMenu" | "Credits" | "Singleplayer" | "Multiplayer";
 
-const appState: Writable<AppState> = writable<AppState>("Singleplayer");
+const appState: Writable<AppState> = writable<AppState>("MainMenu");
 
 export { type AppState, appState };
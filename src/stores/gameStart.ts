@@ -0,0 +1,10 @@
+import { writable, type Writable } from "svelte/store";
+
+// How the singleplayer view should start its game once it has mounted
+type GameStart =
+    | { command: "create_game"; name: string }
+    | { command: "load_game"; name: string };
+
+const gameStart: Writable<GameStart | null> = writable<GameStart | null>(null);
+
+export { type GameStart, gameStart };